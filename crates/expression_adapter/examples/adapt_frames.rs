@@ -0,0 +1,50 @@
+//! Map tracker frames to VRM expressions without Bevy.
+//!
+//! Reads newline-delimited JSON tracker frames (the format written by
+//! `tools/mediapipe_tracker.py`) from stdin and prints one JSON line per frame
+//! with the resulting expression weights:
+//!
+//! ```bash
+//! python tools/mediapipe_tracker.py | cargo run -p expression_adapter --example adapt_frames
+//! ```
+
+use std::io::{self, BufRead, Write};
+
+use expression_adapter::{ArkitToVrmAdapter, BlendshapeToExpression};
+use serde_json::{Map, Value, json};
+use tracker_ipc::TrackerFrame;
+
+fn main() -> io::Result<()> {
+    let adapter = ArkitToVrmAdapter::new();
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let frame: TrackerFrame = match serde_json::from_str(&line) {
+            Ok(frame) => frame,
+            Err(e) => {
+                eprintln!("Warning: Skipping invalid frame: {e}");
+                continue;
+            }
+        };
+
+        let expressions: Map<String, Value> = adapter
+            .to_vrm_expressions(&frame.blendshapes)
+            .into_iter()
+            .map(|e| (e.preset.as_str().to_string(), json!(e.weight)))
+            .collect();
+
+        writeln!(
+            out,
+            "{}",
+            json!({ "ts": frame.ts, "expressions": expressions })
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+//! Neutral-face calibration of raw blendshapes.
+
+use std::collections::HashMap;
+
+/// Resting blendshape baseline captured from a neutral face
+///
+/// Many faces read small nonzero values on several blendshapes at rest (a
+/// slight brow raise or smile). The baseline is subtracted from each
+/// blendshape, clamped at zero, and the remaining range is rescaled so a fully
+/// expressed blendshape still reads 1.0.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpressionCalibration {
+    baseline: HashMap<String, f32>,
+}
+
+impl ExpressionCalibration {
+    /// Create an uncalibrated (all-zero) baseline
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record every blendshape of a neutral-face frame as the baseline
+    pub fn capture_neutral(&mut self, raw_blendshapes: &HashMap<String, f32>) {
+        self.baseline = raw_blendshapes
+            .iter()
+            .map(|(name, &value)| (name.clone(), rest_value(value)))
+            .collect();
+    }
+
+    /// Record a single blendshape of a neutral-face frame as its baseline,
+    /// keeping the others
+    pub fn capture_blendshape(&mut self, raw_blendshapes: &HashMap<String, f32>, name: &str) {
+        let value = raw_blendshapes.get(name).copied().unwrap_or(0.0);
+        self.baseline.insert(name.to_string(), rest_value(value));
+    }
+
+    /// Forget the captured baseline
+    pub fn clear(&mut self) {
+        self.baseline.clear();
+    }
+
+    /// Whether a baseline has been captured
+    pub fn is_calibrated(&self) -> bool {
+        !self.baseline.is_empty()
+    }
+
+    /// Baseline recorded for a blendshape (0.0 if none)
+    pub fn baseline(&self, name: &str) -> f32 {
+        self.baseline.get(name).copied().unwrap_or(0.0)
+    }
+
+    /// Calibrated value of one blendshape, or 0.0 if it is absent
+    pub fn get(&self, raw_blendshapes: &HashMap<String, f32>, name: &str) -> f32 {
+        let value = raw_blendshapes.get(name).copied().unwrap_or(0.0);
+        let baseline = self.baseline(name);
+        ((value - baseline) / (1.0 - baseline)).max(0.0)
+    }
+}
+
+/// Clamp a resting value into a usable baseline
+fn rest_value(value: f32) -> f32 {
+    // Keep the baseline below 1.0 so the rescale never divides by zero
+    value.clamp(0.0, 0.99)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArkitToVrmAdapter, BlendshapeToExpression};
+
+    #[test]
+    fn test_neutral_resting_frame_maps_to_no_expressions() {
+        let resting: HashMap<String, f32> = [
+            ("mouthSmileLeft", 0.4),
+            ("mouthSmileRight", 0.35),
+            ("browInnerUp", 0.2),
+            ("jawOpen", 0.55),
+            ("eyeBlinkLeft", 0.1),
+            ("eyeBlinkRight", 0.1),
+            ("eyeLookUpLeft", 0.05),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+
+        let mut adapter = ArkitToVrmAdapter::new();
+        assert!(!adapter.to_vrm_expressions(&resting).is_empty());
+
+        adapter.neutral.capture_neutral(&resting);
+        assert!(adapter.to_vrm_expressions(&resting).is_empty());
+
+        // Full expressions still reach 1.0 above the baseline
+        let calibration = &adapter.neutral;
+        let mut open = resting.clone();
+        open.insert("jawOpen".to_string(), 1.0);
+        assert!((calibration.get(&open, "jawOpen") - 1.0).abs() < 1e-6);
+        // Values below the baseline clamp at zero
+        open.insert("browInnerUp".to_string(), 0.1);
+        assert_eq!(calibration.get(&open, "browInnerUp"), 0.0);
+    }
+
+    #[test]
+    fn test_mouth_rest_then_neutral_subtracts_jaw_once() {
+        let resting: HashMap<String, f32> = [("jawOpen".to_string(), 0.3)].into();
+        let talking: HashMap<String, f32> = [("jawOpen".to_string(), 0.6)].into();
+        let expected = (0.6 - 0.3) / (1.0 - 0.3);
+
+        // Calibrating the mouth and then the whole face (C, then N)
+        let mut adapter = ArkitToVrmAdapter::new();
+        adapter.calibrate_mouth_rest(&resting);
+        adapter.neutral.capture_neutral(&resting);
+        assert!((adapter.mouth_rest() - 0.3).abs() < 1e-6);
+        assert!((adapter.talk_intensity(&talking) - expected).abs() < 1e-6);
+
+        // And in the opposite order
+        let mut adapter = ArkitToVrmAdapter::new();
+        adapter.neutral.capture_neutral(&resting);
+        adapter.calibrate_mouth_rest(&resting);
+        assert!((adapter.talk_intensity(&talking) - expected).abs() < 1e-6);
+    }
+}
@@ -1,4 +1,28 @@
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+mod calibration;
+mod mixer;
+pub use calibration::ExpressionCalibration;
+pub use mixer::ExpressionMixer;
+
+/// Name of the custom expression driven by [`ArkitToVrmAdapter::eye_wide`]
+///
+/// VRM has no preset for widened eyes, but many models define a custom
+/// expression under this name.
+pub const EYE_WIDE_EXPRESSION: &str = "eyeWide";
+
+/// Custom expression names tried, in order, for a smile on the left side only
+pub const SMILE_LEFT_EXPRESSIONS: &[&str] = &["smileLeft", "smirkLeft"];
+
+/// Custom expression names tried, in order, for a smile on the right side only
+pub const SMILE_RIGHT_EXPRESSIONS: &[&str] = &["smileRight", "smirkRight"];
+
+/// Custom expression names tried, in order, for raised brows
+pub const BROW_UP_EXPRESSIONS: &[&str] = &["browUp", "browInnerUp"];
+
+/// Custom expression names tried, in order, for furrowed brows
+pub const BROW_DOWN_EXPRESSIONS: &[&str] = &["browDown", "browAngry"];
 
 /// Represents a VRM 1.0 expression preset name
 ///
@@ -36,6 +60,56 @@ pub enum VrmExpressionPreset {
 }
 
 impl VrmExpressionPreset {
+    /// Every preset, in declaration order
+    pub const ALL: [VrmExpressionPreset; 18] = [
+        VrmExpressionPreset::Happy,
+        VrmExpressionPreset::Angry,
+        VrmExpressionPreset::Sad,
+        VrmExpressionPreset::Relaxed,
+        VrmExpressionPreset::Surprised,
+        VrmExpressionPreset::Aa,
+        VrmExpressionPreset::Ih,
+        VrmExpressionPreset::Ou,
+        VrmExpressionPreset::Ee,
+        VrmExpressionPreset::Oh,
+        VrmExpressionPreset::Blink,
+        VrmExpressionPreset::BlinkLeft,
+        VrmExpressionPreset::BlinkRight,
+        VrmExpressionPreset::LookUp,
+        VrmExpressionPreset::LookDown,
+        VrmExpressionPreset::LookLeft,
+        VrmExpressionPreset::LookRight,
+        VrmExpressionPreset::Neutral,
+    ];
+
+    /// Get the group this preset belongs to
+    pub fn group(&self) -> ExpressionGroup {
+        match self {
+            VrmExpressionPreset::Happy
+            | VrmExpressionPreset::Angry
+            | VrmExpressionPreset::Sad
+            | VrmExpressionPreset::Relaxed
+            | VrmExpressionPreset::Surprised => ExpressionGroup::Emotions,
+
+            VrmExpressionPreset::Aa
+            | VrmExpressionPreset::Ih
+            | VrmExpressionPreset::Ou
+            | VrmExpressionPreset::Ee
+            | VrmExpressionPreset::Oh => ExpressionGroup::LipSync,
+
+            VrmExpressionPreset::Blink
+            | VrmExpressionPreset::BlinkLeft
+            | VrmExpressionPreset::BlinkRight => ExpressionGroup::Blink,
+
+            VrmExpressionPreset::LookUp
+            | VrmExpressionPreset::LookDown
+            | VrmExpressionPreset::LookLeft
+            | VrmExpressionPreset::LookRight => ExpressionGroup::Gaze,
+
+            VrmExpressionPreset::Neutral => ExpressionGroup::Other,
+        }
+    }
+
     /// Get the canonical VRM expression name as a string
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -70,6 +144,35 @@ impl VrmExpressionPreset {
     }
 }
 
+/// Category of a [`VrmExpressionPreset`], following the VRM 1.0 specification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExpressionGroup {
+    Emotions,
+    LipSync,
+    Blink,
+    Gaze,
+    Other,
+}
+
+impl ExpressionGroup {
+    /// Every group, in the order the presets are declared
+    pub const ALL: [ExpressionGroup; 5] = [
+        ExpressionGroup::Emotions,
+        ExpressionGroup::LipSync,
+        ExpressionGroup::Blink,
+        ExpressionGroup::Gaze,
+        ExpressionGroup::Other,
+    ];
+
+    /// Presets belonging to this group, in declaration order
+    pub fn presets(&self) -> Vec<VrmExpressionPreset> {
+        VrmExpressionPreset::ALL
+            .into_iter()
+            .filter(|preset| preset.group() == *self)
+            .collect()
+    }
+}
+
 /// A VRM expression with its weight value
 #[derive(Debug, Clone)]
 pub struct VrmExpression {
@@ -133,18 +236,315 @@ pub trait BlendshapeToExpression {
 /// - Direct mappings for blink and eye gaze
 /// - Weighted combinations for emotions (e.g., smile → happy)
 /// - Mouth shape to phoneme mappings for lip sync
-pub struct ArkitToVrmAdapter;
+///
+/// The adapter can be calibrated against a neutral face with
+/// [`ArkitToVrmAdapter::calibrate_mouth_rest`] so a mouth that reads slightly
+/// open at rest does not keep the avatar's mouth open. A general neutral-face
+/// baseline for every blendshape can be captured with
+/// [`ExpressionCalibration::capture_neutral`] on [`ArkitToVrmAdapter::neutral`];
+/// both record into the same baseline, so the resting mouth is only
+/// subtracted once.
+#[derive(Debug, Clone, Default)]
+pub struct ArkitToVrmAdapter {
+    /// Tuning options for the mapping
+    pub config: ArkitAdapterConfig,
+
+    /// Neutral-face baseline subtracted from every blendshape before mapping
+    pub neutral: ExpressionCalibration,
+
+    /// Expression names the loaded model defines
+    ///
+    /// Custom expressions from [`ArkitToVrmAdapter::custom_expressions`] are
+    /// only produced for names in this set.
+    pub model_expressions: HashSet<String>,
+}
+
+/// Tuning options for [`ArkitToVrmAdapter`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArkitAdapterConfig {
+    /// How strongly blinks are attenuated while talking (0.0 = off, 1.0 = fully suppressed)
+    ///
+    /// Blink weights are scaled by `1.0 - blink_suppression_during_speech * talk_intensity`,
+    /// which hides spurious blinks MediaPipe reports during energetic speech.
+    pub blink_suppression_during_speech: f32,
+
+    /// How per-eye blinks are combined into the `blink` expression
+    pub blink_combine: BlinkCombine,
+
+    /// How per-eye gaze is combined into the `look*` expressions
+    pub gaze_mode: GazeMode,
+
+    /// How strongly `mouthClose` shuts the open visemes (0.0 = off, 1.0 = full)
+    ///
+    /// `aa` and `oh` are scaled by `1.0 - mouth_close_inhibition * mouthClose`,
+    /// so pressing the lips together closes the avatar's mouth even when
+    /// `jawOpen` stays high.
+    pub mouth_close_inhibition: f32,
+
+    /// Smile asymmetry (`|mouthSmileLeft - mouthSmileRight|`) above which a
+    /// one-sided smile is routed to a custom smirk expression instead of `happy`
+    pub smirk_asymmetry_threshold: f32,
+}
+
+impl Default for ArkitAdapterConfig {
+    fn default() -> Self {
+        Self {
+            blink_suppression_during_speech: 0.0,
+            blink_combine: BlinkCombine::Average,
+            gaze_mode: GazeMode::Independent,
+            mouth_close_inhibition: 0.0,
+            smirk_asymmetry_threshold: 0.3,
+        }
+    }
+}
+
+/// Policy for combining left and right eye blinks into the combined `blink`
+///
+/// The policies differ for winks: with one eye closed and one open, `Average`
+/// half-closes both eyes, `Min` keeps both open and `Max` closes both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlinkCombine {
+    /// Mean of both eyes
+    #[default]
+    Average,
+    /// The more open eye (only full blinks close the eyes)
+    Min,
+    /// The more closed eye (winks close both eyes)
+    Max,
+}
+
+impl BlinkCombine {
+    /// Combine left and right blink values according to this policy
+    pub fn combine(self, left: f32, right: f32) -> f32 {
+        match self {
+            BlinkCombine::Average => (left + right) * 0.5,
+            BlinkCombine::Min => left.min(right),
+            BlinkCombine::Max => left.max(right),
+        }
+    }
+}
+
+/// Policy for combining left and right eye gaze into the `look*` expressions
+///
+/// When the two eyes report inconsistent directions (one looking in, the other
+/// out), `Independent` averages each direction separately and can drive
+/// opposing expressions such as `lookLeft` and `lookRight` at once, which reads
+/// as crossed or diverging eyes. `Conjugate` resolves each axis to a single
+/// direction shared by both eyes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GazeMode {
+    /// Average each direction over both eyes
+    #[default]
+    Independent,
+    /// Blend both eyes into one direction per axis, weighted by how far each eye deflects
+    Conjugate,
+}
+
+impl GazeMode {
+    /// Combine signed per-eye deflections along one axis into a positive and a
+    /// negative direction weight
+    fn combine(self, left: (f32, f32), right: (f32, f32)) -> (f32, f32) {
+        match self {
+            GazeMode::Independent => ((left.0 + right.0) * 0.5, (left.1 + right.1) * 0.5),
+            GazeMode::Conjugate => {
+                let left = left.0 - left.1;
+                let right = right.0 - right.1;
+                let confidence = left.abs() + right.abs();
+                if confidence <= 0.0 {
+                    return (0.0, 0.0);
+                }
+                // Each eye is weighted by its own deflection, so the eye that
+                // clearly looks somewhere dominates one that barely moves
+                let direction = (left * left.abs() + right * right.abs()) / confidence;
+                (direction.max(0.0), (-direction).max(0.0))
+            }
+        }
+    }
+}
+
+/// Mapping from the four `look*` expression weights to eye bone angles
+///
+/// VRM look-at in bone mode rotates the eye bones by a combined yaw and pitch
+/// rather than blending directional expressions. A full `look*` weight maps to
+/// the configured maximum angle.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LookAtBoneRange {
+    /// Yaw in degrees reached at a full `lookLeft`/`lookRight` weight
+    pub max_yaw_degrees: f32,
+    /// Pitch in degrees reached at a full `lookUp`/`lookDown` weight
+    pub max_pitch_degrees: f32,
+}
+
+impl Default for LookAtBoneRange {
+    fn default() -> Self {
+        // Matches the VRM 1.0 default range map output scale
+        Self {
+            max_yaw_degrees: 10.0,
+            max_pitch_degrees: 10.0,
+        }
+    }
+}
+
+impl LookAtBoneRange {
+    /// Combine the `look*` expressions into signed `(yaw, pitch)` degrees
+    ///
+    /// Opposing directions cancel out. Following the VRM convention, positive
+    /// yaw looks left and positive pitch looks up.
+    pub fn yaw_pitch(&self, expressions: &[VrmExpression]) -> (f32, f32) {
+        let weight = |preset: VrmExpressionPreset| {
+            expressions
+                .iter()
+                .filter(|e| e.preset == preset)
+                .map(|e| e.weight)
+                .fold(0.0, f32::max)
+        };
+        let yaw = weight(VrmExpressionPreset::LookLeft) - weight(VrmExpressionPreset::LookRight);
+        let pitch = weight(VrmExpressionPreset::LookUp) - weight(VrmExpressionPreset::LookDown);
+        (yaw * self.max_yaw_degrees, pitch * self.max_pitch_degrees)
+    }
+}
+
+impl ArkitToVrmAdapter {
+    /// Create an uncalibrated adapter with the default configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an uncalibrated adapter with the given configuration
+    pub fn with_config(config: ArkitAdapterConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Estimate how actively the mouth is moving for speech (0.0-1.0)
+    ///
+    /// This is the strongest of the mouth shapes that drive the lip sync
+    /// visemes, after removing the calibrated mouth rest.
+    pub fn talk_intensity(&self, raw_blendshapes: &HashMap<String, f32>) -> f32 {
+        let get = |name: &str| -> f32 { self.neutral.get(raw_blendshapes, name) };
+
+        get("jawOpen")
+            .max(get("mouthFunnel"))
+            .max(get("mouthPucker"))
+            .clamp(0.0, 1.0)
+    }
+
+    /// Estimate how widely the eyes are opened (0.0-1.0)
+    ///
+    /// This is the mean of `eyeWideLeft` and `eyeWideRight`, meant for the
+    /// [`EYE_WIDE_EXPRESSION`] custom expression. It is separate from blink
+    /// and gaze, and does not drive the `surprised` emotion.
+    pub fn eye_wide(&self, raw_blendshapes: &HashMap<String, f32>) -> f32 {
+        let get = |name: &str| -> f32 { self.neutral.get(raw_blendshapes, name) };
+
+        ((get("eyeWideLeft") + get("eyeWideRight")) * 0.5).clamp(0.0, 1.0)
+    }
+
+    /// Weights for the custom expressions the loaded model defines
+    ///
+    /// VRM has no presets for these, so they are returned by name rather than
+    /// as [`VrmExpression`]s. Only names in
+    /// [`ArkitToVrmAdapter::model_expressions`] are produced.
+    pub fn custom_expressions(
+        &self,
+        raw_blendshapes: &HashMap<String, f32>,
+    ) -> Vec<(&'static str, f32)> {
+        let mut expressions = Vec::new();
+
+        let eye_wide = self.eye_wide(raw_blendshapes);
+        if eye_wide > 0.0 && self.model_expressions.contains(EYE_WIDE_EXPRESSION) {
+            expressions.push((EYE_WIDE_EXPRESSION, eye_wide));
+        }
+        if let Some(smirk) = self.smirk(raw_blendshapes) {
+            expressions.push(smirk);
+        }
+
+        // Brows: inner or outer raise lifts them, browDown furrows them
+        let get = |name: &str| -> f32 { self.neutral.get(raw_blendshapes, name) };
+        let brow_up = get("browInnerUp")
+            .max((get("browOuterUpLeft") + get("browOuterUpRight")) * 0.5)
+            .min(1.0);
+        let brow_down = ((get("browDownLeft") + get("browDownRight")) * 0.5).min(1.0);
+        for (candidates, weight) in [
+            (BROW_UP_EXPRESSIONS, brow_up),
+            (BROW_DOWN_EXPRESSIONS, brow_down),
+        ] {
+            if weight > 0.0
+                && let Some(name) = self.first_model_expression(candidates)
+            {
+                expressions.push((name, weight));
+            }
+        }
+
+        expressions
+    }
+
+    /// One-sided smile routed to a custom expression, if the smile is
+    /// asymmetric enough and the model defines a matching expression
+    fn smirk(&self, raw_blendshapes: &HashMap<String, f32>) -> Option<(&'static str, f32)> {
+        let left = self.neutral.get(raw_blendshapes, "mouthSmileLeft");
+        let right = self.neutral.get(raw_blendshapes, "mouthSmileRight");
+        if (left - right).abs() <= self.config.smirk_asymmetry_threshold {
+            return None;
+        }
+
+        let candidates = if left > right {
+            SMILE_LEFT_EXPRESSIONS
+        } else {
+            SMILE_RIGHT_EXPRESSIONS
+        };
+        let name = self.first_model_expression(candidates)?;
+        Some((name, left.max(right).min(1.0)))
+    }
+
+    /// First of the candidate names the loaded model defines
+    fn first_model_expression(&self, candidates: &[&'static str]) -> Option<&'static str> {
+        candidates
+            .iter()
+            .copied()
+            .find(|name| self.model_expressions.contains(*name))
+    }
+
+    /// Record the resting `jawOpen` baseline from a neutral-face frame
+    ///
+    /// The baseline is subtracted from `jawOpen` before lip sync mapping, and the
+    /// remaining range is rescaled so a fully open jaw still maps to 1.0. It
+    /// replaces the `jawOpen` entry of [`ArkitToVrmAdapter::neutral`], leaving
+    /// the other blendshapes' baselines alone.
+    pub fn calibrate_mouth_rest(&mut self, raw_blendshapes: &HashMap<String, f32>) {
+        self.neutral.capture_blendshape(raw_blendshapes, "jawOpen");
+    }
+
+    /// Resting `jawOpen` value measured on a neutral face (0.0 = uncalibrated)
+    pub fn mouth_rest(&self) -> f32 {
+        self.neutral.baseline("jawOpen")
+    }
+
+    /// Scale applied to the open visemes to honour `mouthClose`
+    fn mouth_open_scale(&self, mouth_close: f32) -> f32 {
+        1.0 - self.config.mouth_close_inhibition.clamp(0.0, 1.0) * mouth_close.clamp(0.0, 1.0)
+    }
+}
 
 impl BlendshapeToExpression for ArkitToVrmAdapter {
     fn to_vrm_expressions(&self, raw_blendshapes: &HashMap<String, f32>) -> Vec<VrmExpression> {
         let mut expressions = Vec::new();
 
         // Helper to get blendshape value
-        let get = |name: &str| -> f32 { raw_blendshapes.get(name).copied().unwrap_or(0.0) };
+        let get = |name: &str| -> f32 { self.neutral.get(raw_blendshapes, name) };
 
-        // Blink - direct mapping
-        let blink_left = get("eyeBlinkLeft");
-        let blink_right = get("eyeBlinkRight");
+        // Blink - direct mapping, attenuated while talking
+        let blink_scale = 1.0
+            - self.config.blink_suppression_during_speech.clamp(0.0, 1.0)
+                * self.talk_intensity(raw_blendshapes);
+        let blink_left = get("eyeBlinkLeft") * blink_scale;
+        let blink_right = get("eyeBlinkRight") * blink_scale;
 
         if blink_left > 0.0 {
             expressions.push(VrmExpression::new(
@@ -159,17 +559,22 @@ impl BlendshapeToExpression for ArkitToVrmAdapter {
             ));
         }
 
-        // Combined blink (average of both eyes)
-        let blink = (blink_left + blink_right) * 0.5;
+        // Combined blink
+        let blink = self.config.blink_combine.combine(blink_left, blink_right);
         if blink > 0.0 {
             expressions.push(VrmExpression::new(VrmExpressionPreset::Blink, blink));
         }
 
-        // Eye gaze - direct mapping
-        let look_up = (get("eyeLookUpLeft") + get("eyeLookUpRight")) * 0.5;
-        let look_down = (get("eyeLookDownLeft") + get("eyeLookDownRight")) * 0.5;
-        let look_left = (get("eyeLookInLeft") + get("eyeLookOutRight")) * 0.5;
-        let look_right = (get("eyeLookOutLeft") + get("eyeLookInRight")) * 0.5;
+        // Eye gaze - direct mapping, combined per axis
+        let gaze_mode = self.config.gaze_mode;
+        let (look_up, look_down) = gaze_mode.combine(
+            (get("eyeLookUpLeft"), get("eyeLookDownLeft")),
+            (get("eyeLookUpRight"), get("eyeLookDownRight")),
+        );
+        let (look_left, look_right) = gaze_mode.combine(
+            (get("eyeLookInLeft"), get("eyeLookOutLeft")),
+            (get("eyeLookOutRight"), get("eyeLookInRight")),
+        );
 
         if look_up > 0.0 {
             expressions.push(VrmExpression::new(VrmExpressionPreset::LookUp, look_up));
@@ -188,8 +593,9 @@ impl BlendshapeToExpression for ArkitToVrmAdapter {
         }
 
         // Emotions - weighted combinations
+        // One-sided smiles go to a custom smirk expression when the model has one
         let smile = (get("mouthSmileLeft") + get("mouthSmileRight")) * 0.5;
-        if smile > 0.3 {
+        if smile > 0.3 && self.smirk(raw_blendshapes).is_none() {
             expressions.push(VrmExpression::new(VrmExpressionPreset::Happy, smile));
         }
 
@@ -201,8 +607,9 @@ impl BlendshapeToExpression for ArkitToVrmAdapter {
         // Lip sync - map mouth shapes to phonemes
         // This is a simplified mapping; more sophisticated systems would use
         // actual speech recognition or audio analysis
-        let jaw_open = get("jawOpen");
-        let mouth_funnel = get("mouthFunnel");
+        let open_scale = self.mouth_open_scale(get("mouthClose"));
+        let jaw_open = get("jawOpen") * open_scale;
+        let mouth_funnel = get("mouthFunnel") * open_scale;
         let mouth_pucker = get("mouthPucker");
 
         // "aa" - open mouth (as in "father")
@@ -236,6 +643,29 @@ mod tests {
         assert_eq!(VrmExpressionPreset::LookUp.as_str(), "lookUp");
     }
 
+    #[test]
+    fn test_expression_groups_cover_every_preset_once() {
+        let mut grouped = Vec::new();
+        for group in ExpressionGroup::ALL {
+            for preset in group.presets() {
+                assert_eq!(preset.group(), group);
+                grouped.push(preset);
+            }
+        }
+
+        // Groups are listed in declaration order, so concatenating them
+        // reproduces the full preset list exactly once
+        assert_eq!(grouped, VrmExpressionPreset::ALL);
+        assert_eq!(
+            ExpressionGroup::Blink.presets(),
+            vec![
+                VrmExpressionPreset::Blink,
+                VrmExpressionPreset::BlinkLeft,
+                VrmExpressionPreset::BlinkRight,
+            ]
+        );
+    }
+
     #[test]
     fn test_vrm_expression_clamping() {
         let expr = VrmExpression::new(VrmExpressionPreset::Happy, 1.5);
@@ -247,7 +677,7 @@ mod tests {
 
     #[test]
     fn test_arkit_adapter_blink() {
-        let adapter = ArkitToVrmAdapter;
+        let adapter = ArkitToVrmAdapter::new();
         let mut blendshapes = HashMap::new();
         blendshapes.insert("eyeBlinkLeft".to_string(), 0.8);
         blendshapes.insert("eyeBlinkRight".to_string(), 0.9);
@@ -280,7 +710,7 @@ mod tests {
 
     #[test]
     fn test_arkit_adapter_smile() {
-        let adapter = ArkitToVrmAdapter;
+        let adapter = ArkitToVrmAdapter::new();
         let mut blendshapes = HashMap::new();
         blendshapes.insert("mouthSmileLeft".to_string(), 0.7);
         blendshapes.insert("mouthSmileRight".to_string(), 0.7);
@@ -296,7 +726,7 @@ mod tests {
 
     #[test]
     fn test_arkit_adapter_no_weak_emotions() {
-        let adapter = ArkitToVrmAdapter;
+        let adapter = ArkitToVrmAdapter::new();
         let mut blendshapes = HashMap::new();
         // Weak smile below threshold
         blendshapes.insert("mouthSmileLeft".to_string(), 0.2);
@@ -314,7 +744,7 @@ mod tests {
 
     #[test]
     fn test_arkit_adapter_eye_gaze() {
-        let adapter = ArkitToVrmAdapter;
+        let adapter = ArkitToVrmAdapter::new();
         let mut blendshapes = HashMap::new();
         blendshapes.insert("eyeLookUpLeft".to_string(), 0.6);
         blendshapes.insert("eyeLookUpRight".to_string(), 0.4);
@@ -327,4 +757,245 @@ mod tests {
             .unwrap();
         assert!((look_up.weight - 0.5).abs() < 0.01); // Average
     }
+
+    #[test]
+    fn test_look_at_bone_range_resolves_opposing_directions() {
+        let range = LookAtBoneRange {
+            max_yaw_degrees: 20.0,
+            max_pitch_degrees: 10.0,
+        };
+        let expressions = vec![
+            VrmExpression::new(VrmExpressionPreset::LookUp, 0.6),
+            VrmExpression::new(VrmExpressionPreset::LookDown, 0.1),
+            VrmExpression::new(VrmExpressionPreset::LookRight, 0.25),
+        ];
+
+        let (yaw, pitch) = range.yaw_pitch(&expressions);
+        assert!((pitch - 5.0).abs() < 1e-4); // Net upward 0.5 of the range
+        assert!((yaw + 5.0).abs() < 1e-4); // Right is negative yaw
+    }
+
+    #[test]
+    fn test_arkit_adapter_eye_wide() {
+        let adapter = ArkitToVrmAdapter::new();
+        let mut blendshapes = HashMap::new();
+        blendshapes.insert("eyeWideLeft".to_string(), 0.8);
+        blendshapes.insert("eyeWideRight".to_string(), 0.6);
+
+        assert!((adapter.eye_wide(&blendshapes) - 0.7).abs() < 1e-6);
+        assert!(adapter.custom_expressions(&blendshapes).is_empty());
+        let mut adapter = adapter;
+        adapter
+            .model_expressions
+            .insert(EYE_WIDE_EXPRESSION.to_string());
+        assert_eq!(
+            adapter.custom_expressions(&blendshapes),
+            vec![(EYE_WIDE_EXPRESSION, adapter.eye_wide(&blendshapes))]
+        );
+        // Wide eyes are their own signal, not a blink, gaze or emotion
+        assert!(adapter.to_vrm_expressions(&blendshapes).is_empty());
+        assert_eq!(adapter.eye_wide(&HashMap::new()), 0.0);
+    }
+
+    #[test]
+    fn test_arkit_adapter_smirk() {
+        let mut blendshapes = HashMap::new();
+        blendshapes.insert("mouthSmileLeft".to_string(), 0.9);
+        blendshapes.insert("mouthSmileRight".to_string(), 0.1);
+        let is_happy = |expressions: Vec<VrmExpression>| {
+            expressions
+                .iter()
+                .any(|e| e.preset == VrmExpressionPreset::Happy)
+        };
+
+        // Without a smirk expression on the model, fall back to symmetric happy
+        let mut adapter = ArkitToVrmAdapter::new();
+        assert!(adapter.custom_expressions(&blendshapes).is_empty());
+        assert!(is_happy(adapter.to_vrm_expressions(&blendshapes)));
+
+        adapter.model_expressions = ["smileLeft", "smileRight"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(
+            adapter.custom_expressions(&blendshapes),
+            vec![("smileLeft", 0.9)]
+        );
+        assert!(!is_happy(adapter.to_vrm_expressions(&blendshapes)));
+
+        // A symmetric smile stays happy
+        blendshapes.insert("mouthSmileRight".to_string(), 0.8);
+        assert!(adapter.custom_expressions(&blendshapes).is_empty());
+        assert!(is_happy(adapter.to_vrm_expressions(&blendshapes)));
+    }
+
+    #[test]
+    fn test_arkit_adapter_brows() {
+        let mut adapter = ArkitToVrmAdapter::new();
+        adapter.model_expressions = ["browUp", "browAngry"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let mut raised = HashMap::new();
+        raised.insert("browInnerUp".to_string(), 0.3);
+        raised.insert("browOuterUpLeft".to_string(), 0.8);
+        raised.insert("browOuterUpRight".to_string(), 0.6);
+        let brows = adapter.custom_expressions(&raised);
+        assert_eq!(brows.len(), 1);
+        assert_eq!(brows[0].0, "browUp");
+        assert!((brows[0].1 - 0.7).abs() < 1e-6);
+
+        let mut furrowed = HashMap::new();
+        furrowed.insert("browDownLeft".to_string(), 0.6);
+        furrowed.insert("browDownRight".to_string(), 0.4);
+        assert_eq!(
+            adapter.custom_expressions(&furrowed),
+            vec![("browAngry", 0.5)]
+        );
+
+        // Models without brow expressions get none
+        adapter.model_expressions.clear();
+        assert!(adapter.custom_expressions(&raised).is_empty());
+    }
+
+    #[test]
+    fn test_arkit_adapter_mouth_close_inhibition() {
+        let mut pressed_lips = HashMap::new();
+        pressed_lips.insert("jawOpen".to_string(), 0.8);
+        pressed_lips.insert("mouthClose".to_string(), 0.8);
+
+        let aa_weight = |mouth_close_inhibition: f32| {
+            ArkitToVrmAdapter::with_config(ArkitAdapterConfig {
+                mouth_close_inhibition,
+                ..ArkitAdapterConfig::default()
+            })
+            .to_vrm_expressions(&pressed_lips)
+            .into_iter()
+            .find(|e| e.preset == VrmExpressionPreset::Aa)
+            .map(|e| e.weight)
+        };
+
+        // Off by default, so the open jaw drives aa
+        assert_eq!(aa_weight(0.0), Some(0.8));
+        // Closed lips pull the jaw-derived aa below its threshold
+        assert_eq!(aa_weight(1.0), None);
+    }
+
+    #[test]
+    fn test_arkit_adapter_mouth_rest_calibration() {
+        let mut resting = HashMap::new();
+        resting.insert("jawOpen".to_string(), 0.6);
+
+        // Uncalibrated, a resting jawOpen above the threshold opens the mouth
+        let adapter = ArkitToVrmAdapter::new();
+        assert!(
+            adapter
+                .to_vrm_expressions(&resting)
+                .iter()
+                .any(|e| e.preset == VrmExpressionPreset::Aa)
+        );
+
+        let mut adapter = ArkitToVrmAdapter::new();
+        adapter.calibrate_mouth_rest(&resting);
+        assert!(
+            !adapter
+                .to_vrm_expressions(&resting)
+                .iter()
+                .any(|e| e.preset == VrmExpressionPreset::Aa)
+        );
+
+        // A fully open jaw still maps to full Aa after calibration
+        let mut open = HashMap::new();
+        open.insert("jawOpen".to_string(), 1.0);
+        let aa = adapter
+            .to_vrm_expressions(&open)
+            .into_iter()
+            .find(|e| e.preset == VrmExpressionPreset::Aa)
+            .unwrap();
+        assert!((aa.weight - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_arkit_adapter_blink_suppression_during_speech() {
+        let mut blendshapes = HashMap::new();
+        blendshapes.insert("eyeBlinkLeft".to_string(), 0.8);
+        blendshapes.insert("eyeBlinkRight".to_string(), 0.8);
+        blendshapes.insert("jawOpen".to_string(), 0.9);
+
+        let blink_weight = |adapter: &ArkitToVrmAdapter| {
+            adapter
+                .to_vrm_expressions(&blendshapes)
+                .into_iter()
+                .find(|e| e.preset == VrmExpressionPreset::Blink)
+                .map(|e| e.weight)
+                .unwrap_or(0.0)
+        };
+
+        // Disabled by default
+        assert!((blink_weight(&ArkitToVrmAdapter::new()) - 0.8).abs() < 0.01);
+
+        let adapter = ArkitToVrmAdapter::with_config(ArkitAdapterConfig {
+            blink_suppression_during_speech: 0.5,
+            ..ArkitAdapterConfig::default()
+        });
+        // 0.8 * (1.0 - 0.5 * 0.9)
+        assert!((blink_weight(&adapter) - 0.44).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_arkit_adapter_blink_combine_policies() {
+        let mut wink = HashMap::new();
+        wink.insert("eyeBlinkLeft".to_string(), 1.0);
+        wink.insert("eyeBlinkRight".to_string(), 0.0);
+
+        let blink_weight = |blink_combine: BlinkCombine| {
+            ArkitToVrmAdapter::with_config(ArkitAdapterConfig {
+                blink_combine,
+                ..ArkitAdapterConfig::default()
+            })
+            .to_vrm_expressions(&wink)
+            .into_iter()
+            .find(|e| e.preset == VrmExpressionPreset::Blink)
+            .map(|e| e.weight)
+            .unwrap_or(0.0)
+        };
+
+        assert!((blink_weight(BlinkCombine::Average) - 0.5).abs() < 0.01);
+        assert_eq!(blink_weight(BlinkCombine::Min), 0.0);
+        assert_eq!(blink_weight(BlinkCombine::Max), 1.0);
+    }
+
+    #[test]
+    fn test_arkit_adapter_conjugate_gaze() {
+        // Both eyes look inwards (converging) by different amounts
+        let mut crossed = HashMap::new();
+        crossed.insert("eyeLookInLeft".to_string(), 0.8);
+        crossed.insert("eyeLookInRight".to_string(), 0.4);
+
+        let gaze = |gaze_mode: GazeMode| {
+            ArkitToVrmAdapter::with_config(ArkitAdapterConfig {
+                gaze_mode,
+                ..ArkitAdapterConfig::default()
+            })
+            .to_vrm_expressions(&crossed)
+        };
+        let weight = |expressions: &[VrmExpression], preset: VrmExpressionPreset| {
+            expressions
+                .iter()
+                .find(|e| e.preset == preset)
+                .map(|e| e.weight)
+        };
+
+        // Independent averaging drives both opposing directions
+        let independent = gaze(GazeMode::Independent);
+        assert!(weight(&independent, VrmExpressionPreset::LookLeft).is_some());
+        assert!(weight(&independent, VrmExpressionPreset::LookRight).is_some());
+
+        // Conjugate gaze follows the dominant left eye only
+        let conjugate = gaze(GazeMode::Conjugate);
+        let look_left = weight(&conjugate, VrmExpressionPreset::LookLeft).unwrap();
+        assert!((look_left - 0.4).abs() < 0.01);
+        assert_eq!(weight(&conjugate, VrmExpressionPreset::LookRight), None);
+    }
 }
@@ -0,0 +1,189 @@
+//! Crossfading between several expression sources.
+
+use crate::{VrmExpression, VrmExpressionPreset};
+
+/// Blends named expression sources (e.g. live tracking and a triggered
+/// reaction) with individual weights that can crossfade over time
+///
+/// Each source holds the expressions it produced most recently. The blended
+/// output is the sum of every source's expressions scaled by its mix weight,
+/// clamped to 0.0-1.0.
+///
+/// # Example
+///
+/// ```
+/// use expression_adapter::{ExpressionMixer, VrmExpression, VrmExpressionPreset};
+///
+/// let mut mixer = ExpressionMixer::new();
+/// mixer.set_source("tracker", vec![VrmExpression::new(VrmExpressionPreset::Happy, 1.0)]);
+/// mixer.set_mix_weight("tracker", 1.0);
+///
+/// // Fade to a manual reaction over half a second
+/// mixer.set_source("reaction", vec![VrmExpression::new(VrmExpressionPreset::Surprised, 1.0)]);
+/// mixer.crossfade_to("reaction", 0.5);
+/// mixer.update(0.25);
+///
+/// let blended = mixer.blend();
+/// assert_eq!(blended.len(), 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ExpressionMixer {
+    sources: Vec<MixerSource>,
+}
+
+#[derive(Debug, Clone)]
+struct MixerSource {
+    name: String,
+    expressions: Vec<VrmExpression>,
+    /// Current mix weight
+    weight: f32,
+    /// Mix weight the source is fading towards
+    target_weight: f32,
+    /// Change in mix weight per second while fading
+    fade_rate: f32,
+}
+
+impl ExpressionMixer {
+    /// Create a mixer with no sources
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the expressions of a source, adding it with mix weight 0.0 if new
+    pub fn set_source(&mut self, name: &str, expressions: Vec<VrmExpression>) {
+        self.source_mut(name).expressions = expressions;
+    }
+
+    /// Set a source's mix weight immediately, cancelling any fade
+    pub fn set_mix_weight(&mut self, name: &str, weight: f32) {
+        let source = self.source_mut(name);
+        source.weight = weight.clamp(0.0, 1.0);
+        source.target_weight = source.weight;
+    }
+
+    /// Current mix weight of a source (0.0 if unknown)
+    pub fn mix_weight(&self, name: &str) -> f32 {
+        self.sources
+            .iter()
+            .find(|source| source.name == name)
+            .map_or(0.0, |source| source.weight)
+    }
+
+    /// Fade the named source to full weight and every other source out over `duration` seconds
+    pub fn crossfade_to(&mut self, name: &str, duration: f32) {
+        self.source_mut(name);
+
+        for source in self.sources.iter_mut() {
+            source.target_weight = if source.name == name { 1.0 } else { 0.0 };
+            let distance = (source.target_weight - source.weight).abs();
+            source.fade_rate = if duration > 0.0 {
+                distance / duration
+            } else {
+                f32::INFINITY
+            };
+        }
+    }
+
+    /// Advance running fades by `dt` seconds
+    pub fn update(&mut self, dt: f32) {
+        for source in self.sources.iter_mut() {
+            let max_step = source.fade_rate * dt;
+            let delta = source.target_weight - source.weight;
+            source.weight += delta.clamp(-max_step, max_step);
+        }
+    }
+
+    /// Blend all sources into a single list of expressions
+    ///
+    /// Expressions appear in the order they are first found across sources;
+    /// zero weights are left out.
+    pub fn blend(&self) -> Vec<VrmExpression> {
+        let mut weights: Vec<(VrmExpressionPreset, f32)> = Vec::new();
+
+        for source in self.sources.iter().filter(|source| source.weight > 0.0) {
+            for expression in source.expressions.iter() {
+                let contribution = expression.weight * source.weight;
+                match weights
+                    .iter_mut()
+                    .find(|(preset, _)| *preset == expression.preset)
+                {
+                    Some((_, weight)) => *weight += contribution,
+                    None => weights.push((expression.preset, contribution)),
+                }
+            }
+        }
+
+        weights
+            .into_iter()
+            .filter(|(_, weight)| *weight > 0.0)
+            .map(|(preset, weight)| VrmExpression::new(preset, weight))
+            .collect()
+    }
+
+    fn source_mut(&mut self, name: &str) -> &mut MixerSource {
+        let index = match self.sources.iter().position(|source| source.name == name) {
+            Some(index) => index,
+            None => {
+                self.sources.push(MixerSource {
+                    name: name.to_string(),
+                    expressions: Vec::new(),
+                    weight: 0.0,
+                    target_weight: 0.0,
+                    fade_rate: 0.0,
+                });
+                self.sources.len() - 1
+            }
+        };
+        &mut self.sources[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weight_of(expressions: &[VrmExpression], preset: VrmExpressionPreset) -> f32 {
+        expressions
+            .iter()
+            .find(|e| e.preset == preset)
+            .map_or(0.0, |e| e.weight)
+    }
+
+    #[test]
+    fn test_crossfade_from_manual_to_tracker() {
+        let mut mixer = ExpressionMixer::new();
+        mixer.set_source(
+            "manual",
+            vec![VrmExpression::new(VrmExpressionPreset::Surprised, 1.0)],
+        );
+        mixer.set_source(
+            "tracker",
+            vec![VrmExpression::new(VrmExpressionPreset::Happy, 0.8)],
+        );
+        mixer.set_mix_weight("manual", 1.0);
+
+        let blended = mixer.blend();
+        assert_eq!(weight_of(&blended, VrmExpressionPreset::Surprised), 1.0);
+        assert_eq!(weight_of(&blended, VrmExpressionPreset::Happy), 0.0);
+
+        // Crossfade over one second in four frames
+        mixer.crossfade_to("tracker", 1.0);
+        mixer.update(0.25);
+        mixer.update(0.25);
+
+        let blended = mixer.blend();
+        assert!((weight_of(&blended, VrmExpressionPreset::Surprised) - 0.5).abs() < 1e-6);
+        assert!((weight_of(&blended, VrmExpressionPreset::Happy) - 0.4).abs() < 1e-6);
+
+        mixer.update(0.25);
+        mixer.update(0.25);
+        // Further updates stay at the target
+        mixer.update(0.25);
+
+        let blended = mixer.blend();
+        assert_eq!(mixer.mix_weight("manual"), 0.0);
+        assert_eq!(mixer.mix_weight("tracker"), 1.0);
+        assert_eq!(weight_of(&blended, VrmExpressionPreset::Surprised), 0.0);
+        assert!((weight_of(&blended, VrmExpressionPreset::Happy) - 0.8).abs() < 1e-6);
+    }
+}
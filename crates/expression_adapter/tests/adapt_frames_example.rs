@@ -0,0 +1,51 @@
+//! Runs the `adapt_frames` example on a couple of tracker frames.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Path of the example binary, which `cargo test` builds next to the test binaries
+fn example_path() -> PathBuf {
+    let mut path = std::env::current_exe().unwrap();
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path.join("examples")
+        .join(format!("adapt_frames{}", std::env::consts::EXE_SUFFIX))
+}
+
+#[test]
+fn test_adapt_frames_example_maps_stdin_frames() {
+    let mut child = Command::new(example_path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("adapt_frames example was not built");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(
+            b"{\"ts\":1.0,\"blendshapes\":{\"eyeBlinkLeft\":1.0,\"eyeBlinkRight\":1.0}}\n\
+              not json\n\
+              {\"ts\":2.0,\"blendshapes\":{\"mouthSmileLeft\":0.8,\"mouthSmileRight\":0.8}}\n",
+        )
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let lines: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["ts"], 1.0);
+    assert_eq!(lines[0]["expressions"]["blink"], 1.0);
+    assert_eq!(lines[1]["ts"], 2.0);
+    assert!(lines[1]["expressions"]["happy"].as_f64().unwrap() > 0.5);
+}
@@ -1,8 +1,9 @@
 use crossbeam_channel::{Receiver, Sender};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
-    io::{BufRead, BufReader},
+    collections::{BTreeSet, HashMap},
+    io::{self, BufRead, BufReader, Write},
+    path::PathBuf,
     process::{Child, Command, Stdio},
     thread,
 };
@@ -31,26 +32,308 @@ pub struct PoseWorldLandmark {
 #[derive(Debug, Deserialize)]
 pub struct TrackerFrame {
     pub ts: f64,
+    /// Incrementing frame number from the tracker, if it sends one
+    #[serde(default)]
+    pub seq: Option<u64>,
     pub blendshapes: HashMap<String, f32>,
     #[serde(default)]
     pub pose_landmarks: Vec<PoseLandmark>,
     #[serde(default)]
     pub pose_world_landmarks: Vec<PoseWorldLandmark>,
+    /// Camera the frame was captured from, if the tracker reports it
+    ///
+    /// A [`TrackerControl::SetCamera`] has taken effect once frames report
+    /// the new camera; after a failed switch they keep the old one.
+    #[serde(default)]
+    pub camera: Option<CameraSelector>,
+}
+
+/// Number of face frames [`DetectedBlendshapeKeys`] observes by default
+pub const DEFAULT_BLENDSHAPE_DETECTION_FRAMES: usize = 30;
+
+/// Set of blendshape names the tracker reports, accumulated over its first frames
+///
+/// Some MediaPipe configurations emit only a subset of the 52 ARKit
+/// blendshapes. This records the union of keys seen over the first few frames
+/// that contain a face, so a UI can avoid advertising expressions that can
+/// never fire. Frames without blendshapes (no face detected) are not counted.
+#[derive(Debug, Clone)]
+pub struct DetectedBlendshapeKeys {
+    keys: BTreeSet<String>,
+    frames_seen: usize,
+    frames_needed: usize,
+}
+
+impl Default for DetectedBlendshapeKeys {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLENDSHAPE_DETECTION_FRAMES)
+    }
+}
+
+impl DetectedBlendshapeKeys {
+    /// Create a detector that observes `frames_needed` face frames
+    pub fn new(frames_needed: usize) -> Self {
+        Self {
+            keys: BTreeSet::new(),
+            frames_seen: 0,
+            frames_needed,
+        }
+    }
+
+    /// Add the blendshape keys of a frame
+    ///
+    /// Returns `true` on the frame that completes detection.
+    pub fn observe(&mut self, frame: &TrackerFrame) -> bool {
+        if self.is_complete() || frame.blendshapes.is_empty() {
+            return false;
+        }
+
+        self.keys.extend(frame.blendshapes.keys().cloned());
+        self.frames_seen += 1;
+        self.is_complete()
+    }
+
+    /// Whether enough frames have been observed
+    pub fn is_complete(&self) -> bool {
+        self.frames_seen >= self.frames_needed
+    }
+
+    /// Blendshape names seen so far
+    pub fn keys(&self) -> &BTreeSet<String> {
+        &self.keys
+    }
+
+    /// Whether the tracker has reported the named blendshape
+    pub fn contains(&self, name: &str) -> bool {
+        self.keys.contains(name)
+    }
+}
+
+/// Counts frames lost between the tracker and the app from sequence numbers
+///
+/// A frame whose `seq` skips ahead of the previous one counts the skipped
+/// numbers as dropped. Frames without a `seq` are ignored, and a `seq` that
+/// goes backwards (a restarted tracker) starts counting afresh.
+#[derive(Debug, Clone, Default)]
+pub struct FrameGapCounter {
+    last_seq: Option<u64>,
+    received: u64,
+    dropped: u64,
+}
+
+impl FrameGapCounter {
+    /// Create a counter that has seen no frames
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a frame's sequence number
+    ///
+    /// Returns the number of frames missing just before this one.
+    pub fn observe(&mut self, frame: &TrackerFrame) -> u64 {
+        let Some(seq) = frame.seq else {
+            return 0;
+        };
+
+        let gap = match self.last_seq {
+            Some(last) if seq > last => seq - last - 1,
+            _ => 0,
+        };
+        self.last_seq = Some(seq);
+        self.received += 1;
+        self.dropped += gap;
+        gap
+    }
+
+    /// Frames received with a sequence number
+    pub fn received(&self) -> u64 {
+        self.received
+    }
+
+    /// Frames missing from the sequence so far
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Fraction of the sequence that was dropped (0.0 before any frames)
+    pub fn drop_rate(&self) -> f32 {
+        let total = self.received + self.dropped;
+        if total == 0 {
+            0.0
+        } else {
+            self.dropped as f32 / total as f32
+        }
+    }
+}
+
+/// Merges face and pose data arriving on separate streams into single frames
+///
+/// Frames are pushed as they arrive from either stream; each push keeps the
+/// latest blendshapes and/or pose landmarks it contains. On every render tick,
+/// [`FrameCoalescer::tick`] combines the latest data of both streams. Data older
+/// than `max_age` seconds is treated as missing, so a stalled stream does not
+/// freeze its half of the frame.
+#[derive(Debug, Clone)]
+pub struct FrameCoalescer {
+    max_age: f64,
+    face: Option<(f64, HashMap<String, f32>)>,
+    pose: Option<(f64, Vec<PoseLandmark>, Vec<PoseWorldLandmark>)>,
+}
+
+impl FrameCoalescer {
+    /// Create a coalescer that drops stream data older than `max_age` seconds
+    pub fn new(max_age: f64) -> Self {
+        Self {
+            max_age,
+            face: None,
+            pose: None,
+        }
+    }
+
+    /// Record the face and/or pose data of a frame from either stream
+    pub fn push(&mut self, frame: TrackerFrame) {
+        if !frame.blendshapes.is_empty() {
+            self.face = Some((frame.ts, frame.blendshapes));
+        }
+        if !frame.pose_landmarks.is_empty() || !frame.pose_world_landmarks.is_empty() {
+            self.pose = Some((frame.ts, frame.pose_landmarks, frame.pose_world_landmarks));
+        }
+    }
+
+    /// Merge the latest data of both streams as of time `now`
+    ///
+    /// The merged frame's `ts` is the newest of the included streams. Returns
+    /// `None` when neither stream has fresh data.
+    pub fn tick(&self, now: f64) -> Option<TrackerFrame> {
+        let is_fresh = |ts: f64| now - ts <= self.max_age;
+        let face = self.face.as_ref().filter(|(ts, _)| is_fresh(*ts));
+        let pose = self.pose.as_ref().filter(|(ts, _, _)| is_fresh(*ts));
+
+        let ts = match (face, pose) {
+            (None, None) => return None,
+            (Some((face_ts, _)), None) => *face_ts,
+            (None, Some((pose_ts, _, _))) => *pose_ts,
+            (Some((face_ts, _)), Some((pose_ts, _, _))) => face_ts.max(*pose_ts),
+        };
+
+        Some(TrackerFrame {
+            ts,
+            seq: None,
+            blendshapes: face
+                .map(|(_, blendshapes)| blendshapes.clone())
+                .unwrap_or_default(),
+            pose_landmarks: pose
+                .map(|(_, landmarks, _)| landmarks.clone())
+                .unwrap_or_default(),
+            pose_world_landmarks: pose
+                .map(|(_, _, world_landmarks)| world_landmarks.clone())
+                .unwrap_or_default(),
+            camera: None,
+        })
+    }
+}
+
+/// Command sent to the tracker process over its stdin
+///
+/// Each command is written as one JSON line, e.g.
+/// `{"command":"set_camera","camera":1}`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum TrackerControl {
+    /// Switch the tracker to another video device
+    SetCamera { camera: CameraSelector },
+}
+
+/// Video device to capture from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CameraSelector {
+    /// Device index, as used by `--camera`
+    Index(u32),
+    /// Device path or name understood by OpenCV (e.g. `/dev/video2`)
+    Name(String),
+}
+
+/// Write a control command to the tracker's stdin
+pub fn send_control(stdin: &mut impl Write, control: &TrackerControl) -> io::Result<()> {
+    let line = serde_json::to_string(control)?;
+    writeln!(stdin, "{line}")?;
+    stdin.flush()
 }
 
 /// Run Python process and return a Receiver
+///
+/// The child's stdin is piped so [`TrackerControl`] commands can be sent with
+/// [`send_control`].
 pub fn spawn_tracker(
     python: &str,
     script_path: &str,
     extra_args: &[&str],
 ) -> (Child, Receiver<TrackerFrame>) {
-    let mut child = Command::new(python)
-        .arg(script_path)
-        .args(extra_args)
+    spawn_tracker_with(&TrackerCommand::new(python, script_path).args(extra_args))
+}
+
+/// How to launch the tracker process
+///
+/// Extends the interpreter and script of [`spawn_tracker`] with a working
+/// directory and environment variables, for setups that need a specific venv
+/// or `PYTHONPATH`.
+#[derive(Debug, Clone, Default)]
+pub struct TrackerCommand {
+    python: String,
+    script_path: String,
+    args: Vec<String>,
+    current_dir: Option<PathBuf>,
+    envs: Vec<(String, String)>,
+}
+
+impl TrackerCommand {
+    /// Run `script_path` with the `python` interpreter
+    pub fn new(python: &str, script_path: &str) -> Self {
+        Self {
+            python: python.to_string(),
+            script_path: script_path.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Append arguments passed to the script
+    pub fn args<S: AsRef<str>>(mut self, args: &[S]) -> Self {
+        self.args
+            .extend(args.iter().map(|arg| arg.as_ref().to_string()));
+        self
+    }
+
+    /// Run the process in `dir` (relative script paths resolve against it)
+    pub fn current_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
+    /// Set an environment variable for the process
+    pub fn env(mut self, key: &str, value: &str) -> Self {
+        self.envs.push((key.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// Run the tracker process described by `command` and return a Receiver
+///
+/// As with [`spawn_tracker`], the child's stdin is piped for control commands.
+pub fn spawn_tracker_with(command: &TrackerCommand) -> (Child, Receiver<TrackerFrame>) {
+    let mut process = Command::new(&command.python);
+    process
+        .arg(&command.script_path)
+        .args(&command.args)
+        .envs(command.envs.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::piped())
         .stdout(Stdio::piped())
-        .stderr(Stdio::inherit())
-        .spawn()
-        .expect("failed to spawn tracker process");
+        .stderr(Stdio::inherit());
+    if let Some(dir) = &command.current_dir {
+        process.current_dir(dir);
+    }
+
+    let mut child = process.spawn().expect("failed to spawn tracker process");
 
     let stdout = child.stdout.take().expect("no stdout");
 
@@ -73,3 +356,181 @@ fn spawn_stdout_reader(stdout: std::process::ChildStdout, tx: Sender<TrackerFram
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(keys: &[&str]) -> TrackerFrame {
+        TrackerFrame {
+            ts: 0.0,
+            seq: None,
+            blendshapes: keys.iter().map(|k| (k.to_string(), 0.5)).collect(),
+            pose_landmarks: Vec::new(),
+            pose_world_landmarks: Vec::new(),
+            camera: None,
+        }
+    }
+
+    #[test]
+    fn test_detected_blendshape_keys_union() {
+        let mut detected = DetectedBlendshapeKeys::new(3);
+
+        assert!(!detected.observe(&frame(&["eyeBlinkLeft", "jawOpen"])));
+        // Frames without a face do not count towards detection
+        assert!(!detected.observe(&frame(&[])));
+        assert!(!detected.observe(&frame(&["eyeBlinkRight"])));
+        assert!(detected.observe(&frame(&["jawOpen", "mouthClose"])));
+        assert!(detected.is_complete());
+
+        // Later frames no longer change the set
+        assert!(!detected.observe(&frame(&["cheekPuff"])));
+
+        let keys: Vec<&str> = detected.keys().iter().map(String::as_str).collect();
+        assert_eq!(
+            keys,
+            vec!["eyeBlinkLeft", "eyeBlinkRight", "jawOpen", "mouthClose"]
+        );
+        assert!(!detected.contains("cheekPuff"));
+    }
+
+    fn landmark(x: f32) -> PoseLandmark {
+        PoseLandmark {
+            x,
+            y: 0.0,
+            z: 0.0,
+            visibility: 1.0,
+            presence: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_frame_gap_counter_counts_skipped_seq() {
+        let mut counter = FrameGapCounter::new();
+        let with_seq = |seq| TrackerFrame { seq, ..frame(&[]) };
+
+        assert_eq!(counter.observe(&with_seq(Some(0))), 0);
+        assert_eq!(counter.observe(&with_seq(Some(1))), 0);
+        // Frames 2 and 3 never arrived
+        assert_eq!(counter.observe(&with_seq(Some(4))), 2);
+        // Frames without a sequence number are not counted
+        assert_eq!(counter.observe(&with_seq(None)), 0);
+        assert_eq!(counter.observe(&with_seq(Some(5))), 0);
+
+        assert_eq!(counter.received(), 4);
+        assert_eq!(counter.dropped(), 2);
+        assert!((counter.drop_rate() - 2.0 / 6.0).abs() < 1e-6);
+
+        // A restarted tracker does not count as a gap
+        assert_eq!(counter.observe(&with_seq(Some(0))), 0);
+        assert_eq!(counter.dropped(), 2);
+    }
+
+    #[test]
+    fn test_frame_coalescer_merges_streams() {
+        let mut coalescer = FrameCoalescer::new(0.5);
+        assert!(coalescer.tick(0.0).is_none());
+
+        // Face stream at 10 Hz, pose stream at 2.5 Hz, ticked after each face frame
+        let mut merged = Vec::new();
+        for step in 0..8 {
+            let ts = step as f64 * 0.1;
+            let mut face = frame(&["jawOpen"]);
+            face.ts = ts;
+            face.blendshapes
+                .insert("jawOpen".to_string(), step as f32 / 10.0);
+            coalescer.push(face);
+
+            if step % 4 == 0 {
+                coalescer.push(TrackerFrame {
+                    ts,
+                    seq: None,
+                    blendshapes: HashMap::new(),
+                    pose_landmarks: vec![landmark(step as f32)],
+                    pose_world_landmarks: Vec::new(),
+                    camera: None,
+                });
+            }
+
+            merged.push(coalescer.tick(ts).unwrap());
+        }
+
+        // Every merged frame carries the latest face and the latest pose so far
+        assert_eq!(merged[3].blendshapes["jawOpen"], 0.3);
+        assert_eq!(merged[3].pose_landmarks[0].x, 0.0);
+        assert_eq!(merged[5].blendshapes["jawOpen"], 0.5);
+        assert_eq!(merged[5].pose_landmarks[0].x, 4.0);
+        assert_eq!(merged[5].ts, 0.5);
+
+        // Once the pose stream stalls, merged frames keep the face only
+        let stale = coalescer.tick(0.4 + 0.6).unwrap();
+        assert!(stale.pose_landmarks.is_empty());
+        assert_eq!(stale.blendshapes["jawOpen"], 0.7);
+
+        // Nothing is emitted once both streams are stale
+        assert!(coalescer.tick(2.0).is_none());
+    }
+
+    #[test]
+    fn test_send_set_camera_control() {
+        let mut stdin = Vec::new();
+        send_control(
+            &mut stdin,
+            &TrackerControl::SetCamera {
+                camera: CameraSelector::Index(2),
+            },
+        )
+        .unwrap();
+        send_control(
+            &mut stdin,
+            &TrackerControl::SetCamera {
+                camera: CameraSelector::Name("/dev/video4".to_string()),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(stdin).unwrap(),
+            "{\"command\":\"set_camera\",\"camera\":2}\n\
+             {\"command\":\"set_camera\",\"camera\":\"/dev/video4\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_frame_reports_active_camera() {
+        let frame: TrackerFrame =
+            serde_json::from_str(r#"{"ts": 1.0, "blendshapes": {}, "camera": 2}"#).unwrap();
+        assert_eq!(frame.camera, Some(CameraSelector::Index(2)));
+
+        let frame: TrackerFrame =
+            serde_json::from_str(r#"{"ts": 1.0, "blendshapes": {}}"#).unwrap();
+        assert_eq!(frame.camera, None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_spawn_tracker_with_env_and_current_dir() {
+        let dir = std::env::temp_dir().join(format!("tracker_ipc_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("echo_env.sh"),
+            r#"echo "{\"ts\":1.0,\"blendshapes\":{\"$TRACKER_TEST_KEY\":1.0}}""#,
+        )
+        .unwrap();
+
+        // The relative script path only resolves inside `dir`
+        let command = TrackerCommand::new("sh", "echo_env.sh")
+            .current_dir(&dir)
+            .env("TRACKER_TEST_KEY", "jawOpen");
+        let (mut child, rx) = spawn_tracker_with(&command);
+
+        let frame = rx
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("no frame from tracker");
+        child.wait().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(frame.ts, 1.0);
+        assert_eq!(frame.blendshapes.get("jawOpen"), Some(&1.0));
+    }
+}
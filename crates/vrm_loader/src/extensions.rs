@@ -88,6 +88,122 @@ impl Default for VrmMeta {
     }
 }
 
+impl VrmMeta {
+    /// Typed [`avatar_permission`](Self::avatar_permission)
+    pub fn avatar_permission(&self) -> AvatarPermission {
+        AvatarPermission::parse(&self.avatar_permission)
+    }
+
+    /// Typed [`commercial_usage`](Self::commercial_usage)
+    pub fn commercial_usage(&self) -> CommercialUsage {
+        CommercialUsage::parse(&self.commercial_usage)
+    }
+
+    /// Typed [`credit_notation`](Self::credit_notation)
+    pub fn credit_notation(&self) -> CreditNotation {
+        CreditNotation::parse(&self.credit_notation)
+    }
+
+    /// Typed [`modification`](Self::modification)
+    pub fn modification(&self) -> Modification {
+        Modification::parse(&self.modification)
+    }
+}
+
+/// Who may use the model as an avatar (`avatarPermission`).
+///
+/// An empty value is the spec default, `OnlyAuthor`; any other unrecognised
+/// value is `Unknown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AvatarPermission {
+    OnlyAuthor,
+    OnlySeparatelyLicensedPerson,
+    Everyone,
+    Unknown,
+}
+
+impl AvatarPermission {
+    /// Parse the raw VRM string value
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "" | "onlyAuthor" => Self::OnlyAuthor,
+            "onlySeparatelyLicensedPerson" => Self::OnlySeparatelyLicensedPerson,
+            "everyone" => Self::Everyone,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Who may use the model commercially (`commercialUsage`).
+///
+/// An empty value is the spec default, `PersonalNonProfit`; any other
+/// unrecognised value is `Unknown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommercialUsage {
+    PersonalNonProfit,
+    PersonalProfit,
+    Corporation,
+    Unknown,
+}
+
+impl CommercialUsage {
+    /// Parse the raw VRM string value
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "" | "personalNonProfit" => Self::PersonalNonProfit,
+            "personalProfit" => Self::PersonalProfit,
+            "corporation" => Self::Corporation,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Whether the author must be credited (`creditNotation`).
+///
+/// An empty value is the spec default, `Required`; any other unrecognised
+/// value is `Unknown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreditNotation {
+    Required,
+    Unnecessary,
+    Unknown,
+}
+
+impl CreditNotation {
+    /// Parse the raw VRM string value
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "" | "required" => Self::Required,
+            "unnecessary" => Self::Unnecessary,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Whether the model may be modified (`modification`).
+///
+/// An empty value is the spec default, `Prohibited`; any other unrecognised
+/// value is `Unknown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modification {
+    Prohibited,
+    AllowModification,
+    AllowModificationRedistribution,
+    Unknown,
+}
+
+impl Modification {
+    /// Parse the raw VRM string value
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "" | "prohibited" => Self::Prohibited,
+            "allowModification" => Self::AllowModification,
+            "allowModificationRedistribution" => Self::AllowModificationRedistribution,
+            _ => Self::Unknown,
+        }
+    }
+}
+
 /// VRM 1.0 humanoid bone mapping.
 ///
 /// See: <https://github.com/vrm-c/vrm-specification/blob/master/specification/VRMC_vrm-1.0/humanoid.md>
@@ -279,3 +395,183 @@ pub struct VrmExpressions {
     #[serde(default)]
     pub custom: HashMap<String, VrmExpression>,
 }
+
+/// MToon toon shading parameters of a material (from the
+/// `VRMC_materials_mtoon` material extension).
+///
+/// Only the scalar and color factors are parsed; texture references and the
+/// shader itself are left to the consumer. Missing fields take the
+/// specification defaults.
+///
+/// See: <https://github.com/vrm-c/vrm-specification/blob/master/specification/VRMC_materials_mtoon-1.0/README.md>
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MToonMaterial {
+    /// Color of shaded areas (linear RGB)
+    pub shade_color_factor: [f32; 3],
+
+    /// Shifts the boundary between lit and shaded areas
+    pub shading_shift_factor: f32,
+
+    /// Sharpness of the lit/shaded boundary (0.0 = smooth, 1.0 = hard)
+    pub shading_toony_factor: f32,
+
+    /// Rim light color (linear RGB)
+    pub parametric_rim_color_factor: [f32; 3],
+
+    /// Fresnel power of the rim light
+    pub parametric_rim_fresnel_power_factor: f32,
+
+    /// Lift of the rim light
+    pub parametric_rim_lift_factor: f32,
+
+    /// How much the rim light is affected by scene lighting
+    pub rim_lighting_mix_factor: f32,
+
+    /// Outline width mode ("none", "worldCoordinates" or "screenCoordinates")
+    pub outline_width_mode: String,
+
+    /// Outline width, in meters or relative to the screen depending on the mode
+    pub outline_width_factor: f32,
+
+    /// Outline color (linear RGB)
+    pub outline_color_factor: [f32; 3],
+
+    /// How much the outline color is affected by scene lighting
+    pub outline_lighting_mix_factor: f32,
+}
+
+impl Default for MToonMaterial {
+    fn default() -> Self {
+        Self {
+            shade_color_factor: [0.0, 0.0, 0.0],
+            shading_shift_factor: 0.0,
+            shading_toony_factor: 0.9,
+            parametric_rim_color_factor: [0.0, 0.0, 0.0],
+            parametric_rim_fresnel_power_factor: 5.0,
+            parametric_rim_lift_factor: 0.0,
+            rim_lighting_mix_factor: 1.0,
+            outline_width_mode: "none".to_string(),
+            outline_width_factor: 0.0,
+            outline_color_factor: [0.0, 0.0, 0.0],
+            outline_lighting_mix_factor: 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_avatar_permission_parse() {
+        assert_eq!(AvatarPermission::parse(""), AvatarPermission::OnlyAuthor);
+        assert_eq!(
+            AvatarPermission::parse("onlyAuthor"),
+            AvatarPermission::OnlyAuthor
+        );
+        assert_eq!(
+            AvatarPermission::parse("onlySeparatelyLicensedPerson"),
+            AvatarPermission::OnlySeparatelyLicensedPerson
+        );
+        assert_eq!(
+            AvatarPermission::parse("everyone"),
+            AvatarPermission::Everyone
+        );
+        assert_eq!(
+            AvatarPermission::parse("Everyone"),
+            AvatarPermission::Unknown
+        );
+    }
+
+    #[test]
+    fn test_commercial_usage_parse() {
+        assert_eq!(
+            CommercialUsage::parse(""),
+            CommercialUsage::PersonalNonProfit
+        );
+        assert_eq!(
+            CommercialUsage::parse("personalNonProfit"),
+            CommercialUsage::PersonalNonProfit
+        );
+        assert_eq!(
+            CommercialUsage::parse("personalProfit"),
+            CommercialUsage::PersonalProfit
+        );
+        assert_eq!(
+            CommercialUsage::parse("corporation"),
+            CommercialUsage::Corporation
+        );
+        assert_eq!(CommercialUsage::parse("anyone"), CommercialUsage::Unknown);
+    }
+
+    #[test]
+    fn test_credit_notation_parse() {
+        assert_eq!(CreditNotation::parse(""), CreditNotation::Required);
+        assert_eq!(CreditNotation::parse("required"), CreditNotation::Required);
+        assert_eq!(
+            CreditNotation::parse("unnecessary"),
+            CreditNotation::Unnecessary
+        );
+        assert_eq!(CreditNotation::parse("optional"), CreditNotation::Unknown);
+    }
+
+    #[test]
+    fn test_modification_parse() {
+        assert_eq!(Modification::parse(""), Modification::Prohibited);
+        assert_eq!(Modification::parse("prohibited"), Modification::Prohibited);
+        assert_eq!(
+            Modification::parse("allowModification"),
+            Modification::AllowModification
+        );
+        assert_eq!(
+            Modification::parse("allowModificationRedistribution"),
+            Modification::AllowModificationRedistribution
+        );
+        assert_eq!(Modification::parse("free"), Modification::Unknown);
+    }
+
+    #[test]
+    fn test_meta_typed_accessors() {
+        let meta: VrmMeta = serde_json::from_str(
+            r#"{"name": "Licensed", "avatarPermission": "everyone", "commercialUsage": "corporation"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(meta.avatar_permission(), AvatarPermission::Everyone);
+        assert_eq!(meta.commercial_usage(), CommercialUsage::Corporation);
+        assert_eq!(meta.credit_notation(), CreditNotation::Required);
+        assert_eq!(meta.modification(), Modification::Prohibited);
+        // The raw strings are kept as they appear in the file
+        assert_eq!(meta.avatar_permission, "everyone");
+        assert!(meta.credit_notation.is_empty());
+    }
+
+    #[test]
+    fn test_mtoon_material_deserialize() {
+        let mtoon: MToonMaterial = serde_json::from_str(
+            r#"{
+                "specVersion": "1.0",
+                "shadeColorFactor": [0.6, 0.5, 0.5],
+                "shadingShiftFactor": -0.2,
+                "parametricRimColorFactor": [1.0, 1.0, 1.0],
+                "rimLightingMixFactor": 0.5,
+                "outlineWidthMode": "worldCoordinates",
+                "outlineWidthFactor": 0.002,
+                "outlineColorFactor": [0.1, 0.1, 0.1]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(mtoon.shade_color_factor, [0.6, 0.5, 0.5]);
+        assert_eq!(mtoon.shading_shift_factor, -0.2);
+        assert_eq!(mtoon.parametric_rim_color_factor, [1.0, 1.0, 1.0]);
+        assert_eq!(mtoon.rim_lighting_mix_factor, 0.5);
+        assert_eq!(mtoon.outline_width_mode, "worldCoordinates");
+        assert_eq!(mtoon.outline_width_factor, 0.002);
+        assert_eq!(mtoon.outline_color_factor, [0.1, 0.1, 0.1]);
+        // Unspecified fields take the specification defaults
+        assert_eq!(mtoon.shading_toony_factor, 0.9);
+        assert_eq!(mtoon.parametric_rim_fresnel_power_factor, 5.0);
+    }
+}
@@ -0,0 +1,98 @@
+//! Downloading VRM files over http(s).
+//!
+//! Enabled with the `http` feature. Downloads are blocking, so call them from a
+//! worker thread (as the file dialog does) rather than from a Bevy system.
+
+use std::io::Read;
+
+use crate::VrmLoadError;
+
+/// Maximum size of a downloaded VRM file in bytes.
+///
+/// VRM models with large textures commonly exceed ureq's 10 MB default limit.
+pub const MAX_VRM_DOWNLOAD_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Download a VRM file and return its raw bytes.
+///
+/// Network failures and non-success HTTP statuses are reported as
+/// [`VrmLoadError::Http`]. The bytes are not validated; pass them to
+/// [`parse_vrm_bytes`](crate::parse_vrm_bytes) to check they contain a VRM model.
+pub fn fetch_vrm_bytes(url: &str) -> Result<Vec<u8>, VrmLoadError> {
+    let response = ureq::get(url)
+        .call()
+        .map_err(|e| VrmLoadError::Http(format!("{url}: {e}")))?;
+
+    let mut bytes = Vec::new();
+    response
+        .into_body()
+        .into_reader()
+        .take(MAX_VRM_DOWNLOAD_BYTES + 1)
+        .read_to_end(&mut bytes)?;
+
+    if bytes.len() as u64 > MAX_VRM_DOWNLOAD_BYTES {
+        return Err(VrmLoadError::Http(format!(
+            "{url}: response exceeds {MAX_VRM_DOWNLOAD_BYTES} bytes"
+        )));
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::build_glb;
+    use crate::parse_vrm_bytes;
+    use bevy::asset::Handle;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serve a single HTTP response on a local port and return the base URL.
+    fn serve_once(status: &'static str, body: Vec<u8>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            // Consume the request headers
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                line.clear();
+            }
+
+            let header = format!(
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(header.as_bytes()).unwrap();
+            stream.write_all(&body).unwrap();
+        });
+
+        format!("http://{addr}")
+    }
+
+    #[test]
+    fn test_fetch_vrm_bytes_from_mock_server() {
+        let glb = build_glb(
+            br#"{"asset":{"version":"2.0"},"extensions":{"VRMC_vrm":{"specVersion":"1.0","meta":{"name":"Remote"}}}}"#,
+        );
+        let base_url = serve_once("200 OK", glb.clone());
+
+        let bytes = fetch_vrm_bytes(&format!("{base_url}/model.vrm")).unwrap();
+        assert_eq!(bytes, glb);
+
+        let vrm = parse_vrm_bytes(&bytes, Handle::default()).unwrap();
+        assert_eq!(vrm.meta.name, "Remote");
+    }
+
+    #[test]
+    fn test_fetch_vrm_bytes_reports_http_status() {
+        let base_url = serve_once("404 Not Found", Vec::new());
+
+        let result = fetch_vrm_bytes(&format!("{base_url}/missing.vrm"));
+        assert!(matches!(result, Err(VrmLoadError::Http(_))));
+    }
+}
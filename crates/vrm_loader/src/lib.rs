@@ -24,10 +24,14 @@ use bevy::prelude::*;
 use std::collections::HashMap;
 
 pub mod extensions;
+#[cfg(feature = "http")]
+pub mod http;
 pub mod loader;
 pub mod plugin;
 
 pub use extensions::*;
+#[cfg(feature = "http")]
+pub use http::*;
 pub use loader::*;
 pub use plugin::*;
 
@@ -54,6 +58,283 @@ pub struct VrmAsset {
 
     /// First person view configuration
     pub first_person: Option<VrmFirstPerson>,
+
+    /// glTF nodes, indexed by node index, used to resolve VRM node references
+    pub nodes: Vec<VrmNode>,
+
+    /// Number of entries in the glTF `animations` array
+    pub animation_count: usize,
+
+    /// MToon parameters per glTF material, indexed by material index
+    /// (`None` for materials without the `VRMC_materials_mtoon` extension)
+    pub mtoon_materials: Vec<Option<MToonMaterial>>,
+
+    /// Non-fatal problems found while loading
+    pub warnings: Vec<VrmLoadWarning>,
+}
+
+impl VrmAsset {
+    /// Whether the file contains baked glTF animations (e.g. from a VRMA export).
+    ///
+    /// The animations are not played; this only lets callers tell the user.
+    pub fn has_animations(&self) -> bool {
+        self.animation_count > 0
+    }
+
+    /// Flatten every expression's morph target binds into a single listing.
+    ///
+    /// Node indices are resolved to node and mesh names where the glTF node
+    /// data is available. The result is sorted by expression name, node and
+    /// morph index so it is stable across runs.
+    pub fn morph_bindings(&self) -> Vec<ExpressionMorphBinding> {
+        let mut bindings: Vec<ExpressionMorphBinding> = self
+            .expressions
+            .iter()
+            .flat_map(|(name, expression)| {
+                expression.morph_target_binds.iter().map(|bind| {
+                    let node = self.nodes.get(bind.node);
+                    ExpressionMorphBinding {
+                        expression: name.clone(),
+                        node: bind.node,
+                        node_name: node.and_then(|n| n.name.clone()),
+                        mesh_name: node.and_then(|n| n.mesh_name.clone()),
+                        morph_index: bind.index,
+                        weight: bind.weight,
+                    }
+                })
+            })
+            .collect();
+
+        bindings.sort_by(|a, b| {
+            (&a.expression, a.node, a.morph_index).cmp(&(&b.expression, b.node, b.morph_index))
+        });
+        bindings
+    }
+
+    /// Compose a node's transform with those of its ancestors into the
+    /// model's root space.
+    ///
+    /// Returns `None` if the node does not exist or its ancestors form a cycle.
+    pub fn node_world_transform(&self, index: usize) -> Option<Transform> {
+        let mut transform = self.nodes.get(index)?.transform;
+        for parent in node_ancestors(&self.node_parents(), index)? {
+            transform = self.nodes[parent].transform.mul_transform(transform);
+        }
+        Some(transform)
+    }
+
+    /// Height of the `hips` bone above the model origin, in model units.
+    ///
+    /// Returns `None` if the model has no humanoid `hips` bone.
+    pub fn hips_height(&self) -> Option<f32> {
+        let hips = self.humanoid.as_ref()?.human_bones.get("hips")?.node;
+        Some(self.node_world_transform(hips)?.translation.y)
+    }
+
+    /// Report which humanoid bone groups the model has.
+    ///
+    /// A model without a humanoid supports face tracking only.
+    pub fn tracking_capabilities(&self) -> TrackingCapabilities {
+        let Some(humanoid) = &self.humanoid else {
+            return TrackingCapabilities::default();
+        };
+        let has_all = |bones: &[&str]| {
+            bones
+                .iter()
+                .all(|bone| humanoid.human_bones.contains_key(*bone))
+        };
+
+        TrackingCapabilities {
+            head: has_all(&["head"]),
+            spine: has_all(&["hips", "spine"]),
+            arms: has_all(&[
+                "leftUpperArm",
+                "leftLowerArm",
+                "leftHand",
+                "rightUpperArm",
+                "rightLowerArm",
+                "rightHand",
+            ]),
+            legs: has_all(&[
+                "leftUpperLeg",
+                "leftLowerLeg",
+                "leftFoot",
+                "rightUpperLeg",
+                "rightLowerLeg",
+                "rightFoot",
+            ]),
+        }
+    }
+
+    /// Map from each child node index to its parent's index.
+    fn node_parents(&self) -> HashMap<usize, usize> {
+        let mut node_parents = HashMap::new();
+        for (index, node) in self.nodes.iter().enumerate() {
+            for &child in &node.children {
+                node_parents.insert(child, index);
+            }
+        }
+        node_parents
+    }
+
+    /// Arrange the humanoid bones into a tree rooted at `hips`.
+    ///
+    /// Each bone's parent is its nearest ancestor in the glTF node hierarchy
+    /// that is also a humanoid bone, so non-bone nodes in between are skipped.
+    /// Children are ordered by node index. Returns `None` if the model has no
+    /// humanoid or no `hips` bone, or if a bone's ancestors form a cycle.
+    pub fn humanoid_tree(&self) -> Option<HumanoidBoneNode> {
+        let humanoid = self.humanoid.as_ref()?;
+        let hips = humanoid.human_bones.get("hips")?.node;
+
+        let node_parents = self.node_parents();
+        let bone_names: HashMap<usize, &str> = humanoid
+            .human_bones
+            .iter()
+            .map(|(name, bone)| (bone.node, name.as_str()))
+            .collect();
+
+        // Group bones under their nearest humanoid bone ancestor
+        let mut bone_children: HashMap<usize, Vec<usize>> = HashMap::new();
+        for &node in bone_names.keys() {
+            if node == hips {
+                continue;
+            }
+            let ancestors = node_ancestors(&node_parents, node)?;
+            if let Some(&parent) = ancestors
+                .iter()
+                .find(|ancestor| bone_names.contains_key(ancestor))
+            {
+                bone_children.entry(parent).or_default().push(node);
+            }
+        }
+
+        fn build(
+            node: usize,
+            bone_names: &HashMap<usize, &str>,
+            bone_children: &HashMap<usize, Vec<usize>>,
+        ) -> HumanoidBoneNode {
+            let mut children = bone_children.get(&node).cloned().unwrap_or_default();
+            children.sort_unstable();
+            HumanoidBoneNode {
+                bone: bone_names[&node].to_string(),
+                node,
+                children: children
+                    .into_iter()
+                    .map(|child| build(child, bone_names, bone_children))
+                    .collect(),
+            }
+        }
+
+        Some(build(hips, &bone_names, &bone_children))
+    }
+}
+
+/// Ancestors of a node, from its parent up to the root.
+///
+/// Returns `None` if the parent links loop, as in a malformed glTF whose
+/// `children` form a cycle.
+fn node_ancestors(node_parents: &HashMap<usize, usize>, index: usize) -> Option<Vec<usize>> {
+    let mut ancestors = Vec::new();
+    let mut ancestor = node_parents.get(&index);
+    while let Some(&parent) = ancestor {
+        // Every ancestor but the root has a parent link, so a longer chain loops
+        if ancestors.len() == node_parents.len() {
+            return None;
+        }
+        ancestors.push(parent);
+        ancestor = node_parents.get(&parent);
+    }
+    Some(ancestors)
+}
+
+/// Humanoid bone groups a model has, for enabling body tracking features.
+///
+/// Each group counts as present only if all of its VRM required bones are
+/// mapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TrackingCapabilities {
+    /// `head`
+    pub head: bool,
+    /// `hips` and `spine`
+    pub spine: bool,
+    /// Upper arm, lower arm and hand on both sides
+    pub arms: bool,
+    /// Upper leg, lower leg and foot on both sides
+    pub legs: bool,
+}
+
+impl TrackingCapabilities {
+    /// Whether every bone group is present
+    pub fn full_body(&self) -> bool {
+        self.head && self.spine && self.arms && self.legs
+    }
+}
+
+/// A humanoid bone and its child bones, as returned by [`VrmAsset::humanoid_tree`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HumanoidBoneNode {
+    /// Humanoid bone name (e.g. "spine")
+    pub bone: String,
+
+    /// glTF node index of the bone
+    pub node: usize,
+
+    /// Child bones, ordered by node index
+    pub children: Vec<HumanoidBoneNode>,
+}
+
+/// Minimal glTF node information needed to resolve VRM node references.
+#[derive(Debug, Clone, Default)]
+pub struct VrmNode {
+    /// Name of the node, if any
+    pub name: Option<String>,
+
+    /// Index of the mesh attached to this node, if any
+    pub mesh: Option<usize>,
+
+    /// Name of the mesh attached to this node, if any
+    pub mesh_name: Option<String>,
+
+    /// Number of morph targets on the attached mesh, if it has any primitives
+    pub morph_target_count: Option<usize>,
+
+    /// Indices of the node's child nodes
+    pub children: Vec<usize>,
+
+    /// Local transform of the node relative to its parent
+    pub transform: Transform,
+}
+
+impl VrmNode {
+    /// Whether a morph target index exists on this node's mesh.
+    ///
+    /// Indices are accepted when the morph target count is unknown.
+    pub fn has_morph_target(&self, index: usize) -> bool {
+        self.morph_target_count.is_none_or(|count| index < count)
+    }
+}
+
+/// A single morph target bind of an expression, flattened for inspection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpressionMorphBinding {
+    /// Name of the expression (preset or custom)
+    pub expression: String,
+
+    /// glTF node index the bind targets
+    pub node: usize,
+
+    /// Name of the targeted node, if known
+    pub node_name: Option<String>,
+
+    /// Name of the mesh on the targeted node, if known
+    pub mesh_name: Option<String>,
+
+    /// Morph target index within the node's mesh
+    pub morph_index: usize,
+
+    /// Weight for this morph target
+    pub weight: f32,
 }
 
 /// Component marking a spawned VRM entity in the scene.
@@ -88,3 +369,224 @@ pub struct MorphTargetBinding {
     /// Weight/multiplier for this morph target
     pub weight: f32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expression(binds: &[(usize, usize, f32)]) -> VrmExpression {
+        VrmExpression {
+            morph_target_binds: binds
+                .iter()
+                .map(|&(node, index, weight)| VrmMorphTargetBind {
+                    node,
+                    index,
+                    weight,
+                })
+                .collect(),
+            material_color_binds: Vec::new(),
+            texture_transform_binds: Vec::new(),
+            is_binary: false,
+            override_blink: String::new(),
+            override_look_at: String::new(),
+            override_mouth: String::new(),
+        }
+    }
+
+    fn asset(expressions: HashMap<String, VrmExpression>, nodes: Vec<VrmNode>) -> VrmAsset {
+        VrmAsset {
+            gltf: Handle::default(),
+            meta: VrmMeta::default(),
+            humanoid: None,
+            expressions,
+            look_at: None,
+            first_person: None,
+            nodes,
+            animation_count: 0,
+            mtoon_materials: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_morph_bindings_flattens_and_resolves_nodes() {
+        let expressions = HashMap::from([
+            ("happy".to_string(), expression(&[(1, 3, 1.0), (1, 0, 0.5)])),
+            ("blink".to_string(), expression(&[(1, 7, 1.0)])),
+        ]);
+        let nodes = vec![
+            VrmNode::default(),
+            VrmNode {
+                name: Some("Face".to_string()),
+                mesh: Some(0),
+                mesh_name: Some("FaceMesh".to_string()),
+                morph_target_count: Some(8),
+                ..VrmNode::default()
+            },
+        ];
+
+        let bindings = asset(expressions, nodes).morph_bindings();
+
+        assert_eq!(bindings.len(), 3);
+        assert_eq!(bindings[0].expression, "blink");
+        assert_eq!(bindings[0].morph_index, 7);
+        assert_eq!(bindings[1].expression, "happy");
+        assert_eq!(bindings[1].morph_index, 0);
+        assert_eq!(bindings[1].weight, 0.5);
+        assert_eq!(bindings[2].morph_index, 3);
+        assert!(
+            bindings
+                .iter()
+                .all(|b| b.node == 1 && b.mesh_name.as_deref() == Some("FaceMesh"))
+        );
+    }
+
+    #[test]
+    fn test_morph_bindings_without_node_data() {
+        let expressions = HashMap::from([("aa".to_string(), expression(&[(4, 2, 1.0)]))]);
+
+        let bindings = asset(expressions, Vec::new()).morph_bindings();
+
+        assert_eq!(bindings.len(), 1);
+        assert_eq!(bindings[0].node, 4);
+        assert_eq!(bindings[0].node_name, None);
+        assert_eq!(bindings[0].mesh_name, None);
+    }
+
+    #[test]
+    fn test_humanoid_tree() {
+        // Root -> Hips -> Spine -> Chest -> Neck(non-bone) -> Head
+        //              -> LeftUpperLeg
+        let parents_of = [
+            (0, vec![1]),
+            (1, vec![4, 2]),
+            (2, vec![3]),
+            (3, vec![5]),
+            (5, vec![6]),
+        ];
+        let mut nodes = vec![VrmNode::default(); 7];
+        for (parent, children) in parents_of {
+            nodes[parent].children = children;
+        }
+
+        let mut vrm = asset(HashMap::new(), nodes);
+        vrm.humanoid = Some(VrmHumanoid {
+            human_bones: [
+                ("hips", 1),
+                ("spine", 2),
+                ("chest", 3),
+                ("leftUpperLeg", 4),
+                ("head", 6),
+            ]
+            .into_iter()
+            .map(|(name, node)| (name.to_string(), VrmHumanBone { node }))
+            .collect(),
+        });
+
+        let leaf = |bone: &str, node| HumanoidBoneNode {
+            bone: bone.to_string(),
+            node,
+            children: Vec::new(),
+        };
+        assert_eq!(
+            vrm.humanoid_tree(),
+            Some(HumanoidBoneNode {
+                bone: "hips".to_string(),
+                node: 1,
+                children: vec![
+                    HumanoidBoneNode {
+                        bone: "spine".to_string(),
+                        node: 2,
+                        children: vec![HumanoidBoneNode {
+                            bone: "chest".to_string(),
+                            node: 3,
+                            children: vec![leaf("head", 6)],
+                        }],
+                    },
+                    leaf("leftUpperLeg", 4),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_hips_height_includes_ancestor_scale() {
+        // A centimetre rig under a 0.01-scaled root
+        let mut nodes = vec![VrmNode::default(); 2];
+        nodes[0].children = vec![1];
+        nodes[0].transform = Transform::from_scale(Vec3::splat(0.01));
+        nodes[1].transform = Transform::from_xyz(0.0, 90.0, 0.0);
+
+        let mut vrm = asset(HashMap::new(), nodes);
+        assert_eq!(vrm.hips_height(), None);
+
+        vrm.humanoid = Some(VrmHumanoid {
+            human_bones: HashMap::from([("hips".to_string(), VrmHumanBone { node: 1 })]),
+        });
+        assert!((vrm.hips_height().unwrap() - 0.9).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_cyclic_node_children_do_not_hang() {
+        // Nodes 1 and 2 list each other as children
+        let mut nodes = vec![VrmNode::default(); 3];
+        nodes[0].children = vec![1];
+        nodes[1].children = vec![2];
+        nodes[2].children = vec![1];
+
+        let mut vrm = asset(HashMap::new(), nodes);
+        vrm.humanoid = Some(VrmHumanoid {
+            human_bones: HashMap::from([
+                ("hips".to_string(), VrmHumanBone { node: 1 }),
+                ("spine".to_string(), VrmHumanBone { node: 2 }),
+            ]),
+        });
+
+        assert_eq!(vrm.node_world_transform(1), None);
+        assert_eq!(vrm.hips_height(), None);
+        assert!(vrm.humanoid_tree().is_none());
+        // Nodes outside the cycle still resolve
+        assert_eq!(vrm.node_world_transform(0), Some(Transform::IDENTITY));
+    }
+
+    #[test]
+    fn test_tracking_capabilities_without_legs() {
+        let mut vrm = asset(HashMap::new(), Vec::new());
+        assert_eq!(vrm.tracking_capabilities(), TrackingCapabilities::default());
+
+        let bones = [
+            "hips",
+            "spine",
+            "head",
+            "leftUpperArm",
+            "leftLowerArm",
+            "leftHand",
+            "rightUpperArm",
+            "rightLowerArm",
+            "rightHand",
+            // Only one leg
+            "leftUpperLeg",
+            "leftLowerLeg",
+            "leftFoot",
+        ];
+        vrm.humanoid = Some(VrmHumanoid {
+            human_bones: bones
+                .iter()
+                .enumerate()
+                .map(|(node, bone)| (bone.to_string(), VrmHumanBone { node }))
+                .collect(),
+        });
+
+        let capabilities = vrm.tracking_capabilities();
+        assert_eq!(
+            capabilities,
+            TrackingCapabilities {
+                head: true,
+                spine: true,
+                arms: true,
+                legs: false,
+            }
+        );
+        assert!(!capabilities.full_body());
+    }
+}
@@ -6,10 +6,16 @@
 use bevy::asset::{AssetLoader, LoadContext, io::Reader};
 use bevy::gltf::Gltf;
 use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
 
-use crate::{VrmAsset, VrmExpression, VrmMeta, VrmcVrmExtension};
+use crate::{
+    MToonMaterial, VrmAsset, VrmExpression, VrmHumanoid, VrmMeta, VrmNode, VrmcVrmExtension,
+};
 
 /// Asset loader for VRM 1.0 files.
 ///
@@ -20,15 +26,25 @@ use crate::{VrmAsset, VrmExpression, VrmMeta, VrmcVrmExtension};
 #[derive(Default)]
 pub struct VrmLoader;
 
+/// Options for [`VrmLoader`], set with `AssetServer::load_with_settings`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VrmLoaderSettings {
+    /// Reset non-finite node transforms in `VrmAsset::nodes` to the identity
+    ///
+    /// Only the parsed node copy is changed; the glTF scene Bevy spawns keeps
+    /// the original transforms.
+    pub sanitize_non_finite_transforms: bool,
+}
+
 impl AssetLoader for VrmLoader {
     type Asset = VrmAsset;
-    type Settings = ();
+    type Settings = VrmLoaderSettings;
     type Error = VrmLoadError;
 
     async fn load(
         &self,
         reader: &mut (dyn Reader + '_),
-        _settings: &Self::Settings,
+        settings: &Self::Settings,
         load_context: &mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
         // Read the entire VRM file into memory
@@ -36,7 +52,7 @@ impl AssetLoader for VrmLoader {
         reader.read_to_end(&mut bytes).await?;
 
         // Parse as glTF/GLB
-        let vrm_asset = parse_vrm_from_bytes(&bytes, load_context)?;
+        let vrm_asset = parse_vrm_from_bytes(&bytes, settings, load_context)?;
 
         Ok(vrm_asset)
     }
@@ -63,12 +79,84 @@ pub enum VrmLoadError {
 
     #[error("Invalid VRM extension: {0}")]
     InvalidVrmExtension(String),
+
+    #[error("HTTP error: {0}")]
+    Http(String),
+}
+
+/// Non-fatal problem found while loading a VRM file.
+///
+/// Warnings are collected on `VrmAsset::warnings` and logged when the asset
+/// finishes loading; the model is still usable.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum VrmLoadWarning {
+    #[error("Humanoid bone '{bone}' references node {node}, which does not exist")]
+    HumanoidBoneNodeOutOfRange { bone: String, node: usize },
+
+    #[error("Humanoid bone '{bone}' references node {node}, which is not a skin joint")]
+    HumanoidBoneNotJoint { bone: String, node: usize },
+
+    #[error(
+        "Expression '{expression}' binds morph target {index} on node {node}, whose mesh has only {count}"
+    )]
+    MorphTargetIndexOutOfRange {
+        expression: String,
+        node: usize,
+        index: usize,
+        count: usize,
+    },
+
+    #[error(
+        "Expression '{expression}' binds morph target {index} on node {node} with weight {weight}, clamped to [0, 1]"
+    )]
+    MorphTargetBindWeightOutOfRange {
+        expression: String,
+        node: usize,
+        index: usize,
+        weight: f32,
+    },
+
+    #[error("Node {node} has a non-finite transform")]
+    NonFiniteNodeTransform { node: usize },
+
+    #[error("VRMC_vrm section '{section}' is invalid and was skipped: {message}")]
+    InvalidVrmExtensionSection { section: String, message: String },
+
+    #[error("Material {material} has an invalid VRMC_materials_mtoon extension: {message}")]
+    InvalidMToonMaterial { material: usize, message: String },
+
+    #[error("Model contains {count} glTF animation(s), which are not played")]
+    ContainsAnimations { count: usize },
 }
 
 /// Parse VRM data from GLB or glTF bytes.
 fn parse_vrm_from_bytes(
     bytes: &[u8],
+    settings: &VrmLoaderSettings,
     load_context: &mut LoadContext,
+) -> Result<VrmAsset, VrmLoadError> {
+    // Load the glTF asset using Bevy's loader
+    // Use the full asset path (including source) to preserve userdata:// scheme
+    // TODO: In Bevy 0.18+, use path() instead of asset_path() as path() will return AssetPath
+    let asset_path = load_context.asset_path().to_owned();
+    let gltf_handle: Handle<Gltf> = load_context.load(asset_path);
+
+    parse_vrm_bytes_with_settings(bytes, gltf_handle, settings)
+}
+
+/// Parse VRM data from GLB or glTF bytes into a `VrmAsset`.
+///
+/// This is the asset-system independent part of loading: the caller supplies
+/// the handle of the glTF asset holding the model's meshes and scenes.
+pub fn parse_vrm_bytes(bytes: &[u8], gltf: Handle<Gltf>) -> Result<VrmAsset, VrmLoadError> {
+    parse_vrm_bytes_with_settings(bytes, gltf, &VrmLoaderSettings::default())
+}
+
+/// Like [`parse_vrm_bytes`], with the options [`VrmLoader`] uses.
+pub fn parse_vrm_bytes_with_settings(
+    bytes: &[u8],
+    gltf: Handle<Gltf>,
+    settings: &VrmLoaderSettings,
 ) -> Result<VrmAsset, VrmLoadError> {
     // Try to parse as GLB first (most VRM files are GLB format)
     let (json_data, _buffer_data) = if bytes.starts_with(b"glTF") {
@@ -79,32 +167,107 @@ fn parse_vrm_from_bytes(
     };
 
     // Parse the JSON
-    let json: Value = serde_json::from_slice(&json_data)?;
+    let json: Value = serde_json::from_slice(strip_utf8_bom(&json_data))?;
 
     // Extract the VRMC_vrm extension
-    let vrm_extension = extract_vrm_extension(&json)?;
+    let mut warnings = Vec::new();
+    let vrm_extension = extract_vrm_extension(&json, &mut warnings)?;
 
-    // Load the glTF asset using Bevy's loader
-    // Use the full asset path (including source) to preserve userdata:// scheme
-    // TODO: In Bevy 0.18+, use path() instead of asset_path() as path() will return AssetPath
-    let asset_path = load_context.asset_path().to_owned();
-    let gltf_handle: Handle<Gltf> = load_context.load(asset_path);
+    // Extract node names and meshes for resolving VRM node references
+    let mut nodes = extract_nodes(&json);
 
     // Combine preset and custom expressions
     let mut all_expressions = HashMap::new();
     all_expressions.extend(vrm_extension.expressions.preset.clone());
     all_expressions.extend(vrm_extension.expressions.custom.clone());
 
+    warnings.extend(validate_node_transforms(
+        &mut nodes,
+        settings.sanitize_non_finite_transforms,
+    ));
+    if let Some(humanoid) = &vrm_extension.humanoid {
+        warnings.extend(validate_humanoid_joints(
+            humanoid,
+            nodes.len(),
+            &extract_skin_joints(&json),
+        ));
+    }
+
+    warnings.extend(validate_morph_binds(&all_expressions, &nodes));
+    warnings.extend(clamp_morph_bind_weights(&mut all_expressions));
+
+    let mtoon_materials = extract_mtoon_materials(&json, &mut warnings);
+
+    let animation_count = json
+        .get("animations")
+        .and_then(Value::as_array)
+        .map_or(0, Vec::len);
+    if animation_count > 0 {
+        warnings.push(VrmLoadWarning::ContainsAnimations {
+            count: animation_count,
+        });
+    }
+
     Ok(VrmAsset {
-        gltf: gltf_handle,
+        gltf,
         meta: vrm_extension.meta,
         humanoid: vrm_extension.humanoid,
         expressions: all_expressions,
         look_at: vrm_extension.look_at,
         first_person: vrm_extension.first_person,
+        nodes,
+        animation_count,
+        mtoon_materials,
+        warnings,
     })
 }
 
+/// Parse only the VRM metadata from GLB or glTF bytes.
+///
+/// This skips expressions, humanoid and node data, which makes it suitable for
+/// listing many models (e.g. in a model picker) without loading them.
+pub fn parse_vrm_metadata_only(bytes: &[u8]) -> Result<VrmMeta, VrmLoadError> {
+    let json_data = if bytes.starts_with(b"glTF") {
+        parse_glb(bytes)?.0
+    } else {
+        bytes.to_vec()
+    };
+
+    let json: Value = serde_json::from_slice(strip_utf8_bom(&json_data))?;
+
+    let meta = json
+        .get("extensions")
+        .and_then(|extensions| extensions.get("VRMC_vrm"))
+        .ok_or(VrmLoadError::MissingVrmExtension)?
+        .get("meta")
+        .ok_or_else(|| VrmLoadError::InvalidVrmExtension("missing meta".to_string()))?;
+
+    serde_json::from_value(meta.clone())
+        .map_err(|e| VrmLoadError::InvalidVrmExtension(e.to_string()))
+}
+
+/// Read only the VRM metadata from a file on disk.
+///
+/// For GLB files only the header and JSON chunk are read, so the (usually much
+/// larger) binary chunk with meshes and textures is never loaded.
+pub fn read_vrm_metadata_only(path: &Path) -> Result<VrmMeta, VrmLoadError> {
+    let mut file = std::fs::File::open(path)?;
+
+    // GLB header (12 bytes) followed by the JSON chunk header (8 bytes)
+    let mut bytes = Vec::new();
+    file.by_ref().take(20).read_to_end(&mut bytes)?;
+
+    if bytes.len() == 20 && bytes.starts_with(b"glTF") {
+        let json_length = u32::from_le_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]);
+        file.take(json_length as u64).read_to_end(&mut bytes)?;
+    } else {
+        // Plain JSON glTF: the whole file is needed
+        file.read_to_end(&mut bytes)?;
+    }
+
+    parse_vrm_metadata_only(&bytes)
+}
+
 /// Parse GLB binary format.
 ///
 /// GLB structure:
@@ -181,8 +344,17 @@ fn parse_glb(bytes: &[u8]) -> Result<(Vec<u8>, Vec<u8>), VrmLoadError> {
     Ok((json_data, bin_data))
 }
 
+/// Strip a leading UTF-8 byte order mark, which some exporters write before
+/// the JSON and `serde_json` rejects.
+fn strip_utf8_bom(json: &[u8]) -> &[u8] {
+    json.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(json)
+}
+
 /// Extract VRMC_vrm extension from glTF JSON.
-fn extract_vrm_extension(json: &Value) -> Result<VrmcVrmExtension, VrmLoadError> {
+fn extract_vrm_extension(
+    json: &Value,
+    warnings: &mut Vec<VrmLoadWarning>,
+) -> Result<VrmcVrmExtension, VrmLoadError> {
     // Navigate to extensions.VRMC_vrm
     let extensions = json
         .get("extensions")
@@ -192,11 +364,282 @@ fn extract_vrm_extension(json: &Value) -> Result<VrmcVrmExtension, VrmLoadError>
         .get("VRMC_vrm")
         .ok_or(VrmLoadError::MissingVrmExtension)?;
 
-    // Deserialize the VRM extension
-    let vrm_extension: VrmcVrmExtension = serde_json::from_value(vrmc_vrm.clone())
-        .map_err(|e| VrmLoadError::InvalidVrmExtension(e.to_string()))?;
+    // The spec version and meta are required; without them the file is not VRM
+    let required = |key: &str| {
+        vrmc_vrm
+            .get(key)
+            .ok_or_else(|| VrmLoadError::InvalidVrmExtension(format!("missing {key}")))
+    };
+    let spec_version = serde_json::from_value(required("specVersion")?.clone())
+        .map_err(|e| VrmLoadError::InvalidVrmExtension(format!("specVersion: {e}")))?;
+    let meta = serde_json::from_value(required("meta")?.clone())
+        .map_err(|e| VrmLoadError::InvalidVrmExtension(format!("meta: {e}")))?;
+
+    // Other sections are parsed independently, so one malformed section does
+    // not lose the rest of the model
+    Ok(VrmcVrmExtension {
+        spec_version,
+        meta,
+        humanoid: extract_optional_section(vrmc_vrm, "humanoid", warnings),
+        expressions: extract_optional_section(vrmc_vrm, "expressions", warnings)
+            .unwrap_or_default(),
+        look_at: extract_optional_section(vrmc_vrm, "lookAt", warnings),
+        first_person: extract_optional_section(vrmc_vrm, "firstPerson", warnings),
+    })
+}
 
-    Ok(vrm_extension)
+/// Deserialize an optional VRMC_vrm section, recording a warning if it is invalid.
+fn extract_optional_section<T: DeserializeOwned>(
+    vrmc_vrm: &Value,
+    key: &str,
+    warnings: &mut Vec<VrmLoadWarning>,
+) -> Option<T> {
+    let value = vrmc_vrm.get(key).filter(|value| !value.is_null())?;
+    serde_json::from_value(value.clone())
+        .map_err(|e| {
+            warnings.push(VrmLoadWarning::InvalidVrmExtensionSection {
+                section: key.to_string(),
+                message: e.to_string(),
+            })
+        })
+        .ok()
+}
+
+/// Extract the MToon parameters of each glTF material.
+///
+/// Materials whose extension fails to deserialize are reported as warnings and
+/// treated as having no MToon parameters.
+fn extract_mtoon_materials(
+    json: &Value,
+    warnings: &mut Vec<VrmLoadWarning>,
+) -> Vec<Option<MToonMaterial>> {
+    let Some(materials) = json.get("materials").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+
+    materials
+        .iter()
+        .enumerate()
+        .map(|(index, material)| {
+            let mtoon = material.get("extensions")?.get("VRMC_materials_mtoon")?;
+            serde_json::from_value(mtoon.clone())
+                .map_err(|e| {
+                    warnings.push(VrmLoadWarning::InvalidMToonMaterial {
+                        material: index,
+                        message: e.to_string(),
+                    })
+                })
+                .ok()
+        })
+        .collect()
+}
+
+/// Extract node names and attached meshes from glTF JSON.
+///
+/// Missing or malformed entries yield empty `VrmNode`s so node indices stay aligned.
+fn extract_nodes(json: &Value) -> Vec<VrmNode> {
+    let meshes: &[Value] = json
+        .get("meshes")
+        .and_then(Value::as_array)
+        .map_or(&[], Vec::as_slice);
+    let mesh_names: Vec<Option<String>> = meshes
+        .iter()
+        .map(|mesh| mesh.get("name").and_then(Value::as_str).map(str::to_string))
+        .collect();
+    // glTF requires every primitive of a mesh to have the same number of targets
+    let morph_target_counts: Vec<Option<usize>> = meshes
+        .iter()
+        .map(|mesh| {
+            let primitive = mesh.get("primitives")?.as_array()?.first()?;
+            Some(
+                primitive
+                    .get("targets")
+                    .and_then(Value::as_array)
+                    .map_or(0, Vec::len),
+            )
+        })
+        .collect();
+
+    let Some(nodes) = json.get("nodes").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+
+    nodes
+        .iter()
+        .map(|node| {
+            let mesh = node
+                .get("mesh")
+                .and_then(Value::as_u64)
+                .map(|index| index as usize);
+            VrmNode {
+                name: node.get("name").and_then(Value::as_str).map(str::to_string),
+                mesh,
+                mesh_name: mesh.and_then(|index| mesh_names.get(index).cloned().flatten()),
+                morph_target_count: mesh
+                    .and_then(|index| morph_target_counts.get(index).copied().flatten()),
+                children: node
+                    .get("children")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|child| child.as_u64().map(|index| index as usize))
+                    .collect(),
+                transform: extract_node_transform(node),
+            }
+        })
+        .collect()
+}
+
+/// Collect the node indices used as joints by any glTF skin.
+fn extract_skin_joints(json: &Value) -> HashSet<usize> {
+    json.get("skins")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|skin| skin.get("joints").and_then(Value::as_array))
+        .flatten()
+        .filter_map(|joint| joint.as_u64().map(|index| index as usize))
+        .collect()
+}
+
+/// Check that every humanoid bone points at an existing skin joint node.
+///
+/// Models without any skin joints (rigid, unskinned models) only have their
+/// node indices range-checked.
+fn validate_humanoid_joints(
+    humanoid: &VrmHumanoid,
+    node_count: usize,
+    skin_joints: &HashSet<usize>,
+) -> Vec<VrmLoadWarning> {
+    let mut bones: Vec<_> = humanoid.human_bones.iter().collect();
+    bones.sort_by_key(|(name, _)| name.as_str());
+
+    bones
+        .into_iter()
+        .filter_map(|(name, bone)| {
+            if bone.node >= node_count {
+                Some(VrmLoadWarning::HumanoidBoneNodeOutOfRange {
+                    bone: name.clone(),
+                    node: bone.node,
+                })
+            } else if !skin_joints.is_empty() && !skin_joints.contains(&bone.node) {
+                Some(VrmLoadWarning::HumanoidBoneNotJoint {
+                    bone: name.clone(),
+                    node: bone.node,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Read a node's local transform from its `matrix` or TRS properties.
+///
+/// Missing or malformed properties fall back to the identity.
+fn extract_node_transform(node: &Value) -> Transform {
+    let floats = |key: &str| -> Option<Vec<f32>> {
+        node.get(key)?
+            .as_array()?
+            .iter()
+            .map(|value| value.as_f64().map(|value| value as f32))
+            .collect()
+    };
+
+    if let Some(matrix) = floats("matrix").filter(|matrix| matrix.len() == 16) {
+        // glTF matrices are column-major, like glam's
+        return Transform::from_matrix(Mat4::from_cols_slice(&matrix));
+    }
+
+    let mut transform = Transform::IDENTITY;
+    if let Some(&[x, y, z]) = floats("translation").as_deref() {
+        transform.translation = Vec3::new(x, y, z);
+    }
+    if let Some(&[x, y, z, w]) = floats("rotation").as_deref() {
+        transform.rotation = Quat::from_xyzw(x, y, z, w).normalize();
+    }
+    if let Some(&[x, y, z]) = floats("scale").as_deref() {
+        transform.scale = Vec3::new(x, y, z);
+    }
+    transform
+}
+
+/// Flag node transforms containing NaN or infinity, e.g. from a zero rotation
+/// quaternion or values overflowing `f32`.
+///
+/// This is diagnostic: the spawned glTF scene keeps the corrupt transforms.
+/// With `sanitize`, the `VrmAsset::nodes` copy is reset to the identity so
+/// calculations over it (e.g. hips height) stay finite.
+fn validate_node_transforms(nodes: &mut [VrmNode], sanitize: bool) -> Vec<VrmLoadWarning> {
+    let mut warnings = Vec::new();
+    for (index, node) in nodes.iter_mut().enumerate() {
+        if node.transform.is_finite() {
+            continue;
+        }
+        warnings.push(VrmLoadWarning::NonFiniteNodeTransform { node: index });
+        if sanitize {
+            node.transform = Transform::IDENTITY;
+        }
+    }
+    warnings
+}
+
+/// Check that every morph target bind points at a morph target its mesh has.
+///
+/// Binds on nodes that do not exist or whose mesh has unknown morph targets
+/// are not checked.
+fn validate_morph_binds(
+    expressions: &HashMap<String, VrmExpression>,
+    nodes: &[VrmNode],
+) -> Vec<VrmLoadWarning> {
+    let mut expressions: Vec<_> = expressions.iter().collect();
+    expressions.sort_by_key(|(name, _)| name.as_str());
+
+    expressions
+        .into_iter()
+        .flat_map(|(name, expression)| {
+            expression.morph_target_binds.iter().filter_map(|bind| {
+                let node = nodes.get(bind.node)?;
+                let count = node.morph_target_count?;
+                (!node.has_morph_target(bind.index)).then(|| {
+                    VrmLoadWarning::MorphTargetIndexOutOfRange {
+                        expression: name.clone(),
+                        node: bind.node,
+                        index: bind.index,
+                        count,
+                    }
+                })
+            })
+        })
+        .collect()
+}
+
+/// Clamp morph target bind weights into `[0, 1]`, as the spec requires.
+///
+/// An out-of-range weight such as 5.0 would over-drive the morph, so each
+/// clamped bind is reported.
+fn clamp_morph_bind_weights(
+    expressions: &mut HashMap<String, VrmExpression>,
+) -> Vec<VrmLoadWarning> {
+    let mut expressions: Vec<_> = expressions.iter_mut().collect();
+    expressions.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut warnings = Vec::new();
+    for (name, expression) in expressions {
+        for bind in expression.morph_target_binds.iter_mut() {
+            if (0.0..=1.0).contains(&bind.weight) {
+                continue;
+            }
+            warnings.push(VrmLoadWarning::MorphTargetBindWeightOutOfRange {
+                expression: name.clone(),
+                node: bind.node,
+                index: bind.index,
+                weight: bind.weight,
+            });
+            bind.weight = bind.weight.clamp(0.0, 1.0);
+        }
+    }
+    warnings
 }
 
 /// Print VRM metadata to console.
@@ -250,3 +693,324 @@ pub fn print_vrm_expressions(expressions: &HashMap<String, VrmExpression>) {
 
     println!("=======================\n");
 }
+
+/// Build a GLB container around a JSON chunk, for tests.
+#[cfg(test)]
+pub(crate) fn build_glb(json: &[u8]) -> Vec<u8> {
+    // Chunks are 4-byte aligned; JSON is padded with spaces
+    let mut json = json.to_vec();
+    while json.len() % 4 != 0 {
+        json.push(b' ');
+    }
+
+    let total_length = 12 + 8 + json.len();
+    let mut glb = Vec::with_capacity(total_length);
+    glb.extend_from_slice(b"glTF");
+    glb.extend_from_slice(&2u32.to_le_bytes());
+    glb.extend_from_slice(&(total_length as u32).to_le_bytes());
+    glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    glb.extend_from_slice(&0x4E4F534Au32.to_le_bytes());
+    glb.extend_from_slice(&json);
+    glb
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINIMAL_VRM_JSON: &[u8] = br#"{
+        "asset": {"version": "2.0"},
+        "nodes": [{"name": "Body", "mesh": 0}],
+        "meshes": [{"name": "BodyMesh"}],
+        "extensions": {"VRMC_vrm": {"specVersion": "1.0", "meta": {"name": "Minimal"}}}
+    }"#;
+
+    #[test]
+    fn test_parse_vrm_bytes_glb_and_json() {
+        let from_glb = parse_vrm_bytes(&build_glb(MINIMAL_VRM_JSON), Handle::default()).unwrap();
+        let from_json = parse_vrm_bytes(MINIMAL_VRM_JSON, Handle::default()).unwrap();
+
+        for vrm in [from_glb, from_json] {
+            assert_eq!(vrm.meta.name, "Minimal");
+            assert_eq!(vrm.nodes.len(), 1);
+            assert_eq!(vrm.nodes[0].mesh_name.as_deref(), Some("BodyMesh"));
+        }
+    }
+
+    #[test]
+    fn test_parse_vrm_bytes_with_utf8_bom() {
+        let with_bom = [b"\xEF\xBB\xBF".as_slice(), MINIMAL_VRM_JSON].concat();
+
+        let from_glb = parse_vrm_bytes(&build_glb(&with_bom), Handle::default()).unwrap();
+        let from_json = parse_vrm_bytes(&with_bom, Handle::default()).unwrap();
+        assert_eq!(from_glb.meta.name, "Minimal");
+        assert_eq!(from_json.meta.name, "Minimal");
+
+        let meta = parse_vrm_metadata_only(&build_glb(&with_bom)).unwrap();
+        assert_eq!(meta.name, "Minimal");
+    }
+
+    #[test]
+    fn test_humanoid_bone_not_joint_warning() {
+        let json = br#"{
+            "asset": {"version": "2.0"},
+            "nodes": [{"name": "Hips"}, {"name": "Spine"}, {"name": "Prop"}],
+            "skins": [{"joints": [0, 1]}],
+            "extensions": {"VRMC_vrm": {
+                "specVersion": "1.0",
+                "meta": {"name": "Skinned"},
+                "humanoid": {"humanBones": {
+                    "hips": {"node": 0},
+                    "spine": {"node": 1},
+                    "chest": {"node": 2},
+                    "head": {"node": 9}
+                }}
+            }}
+        }"#;
+
+        let vrm = parse_vrm_bytes(json, Handle::default()).unwrap();
+
+        assert_eq!(
+            vrm.warnings,
+            vec![
+                VrmLoadWarning::HumanoidBoneNotJoint {
+                    bone: "chest".to_string(),
+                    node: 2,
+                },
+                VrmLoadWarning::HumanoidBoneNodeOutOfRange {
+                    bone: "head".to_string(),
+                    node: 9,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_morph_target_index_out_of_range_warning() {
+        let json = br#"{
+            "asset": {"version": "2.0"},
+            "nodes": [{"name": "Face", "mesh": 0}],
+            "meshes": [{"name": "FaceMesh", "primitives": [{"attributes": {}, "targets": [{}, {}, {}]}]}],
+            "extensions": {"VRMC_vrm": {
+                "specVersion": "1.0",
+                "meta": {"name": "Mismatched"},
+                "expressions": {"preset": {
+                    "happy": {"morphTargetBinds": [
+                        {"node": 0, "index": 2, "weight": 1.0},
+                        {"node": 0, "index": 7, "weight": 1.0}
+                    ]}
+                }}
+            }}
+        }"#;
+
+        let vrm = parse_vrm_bytes(json, Handle::default()).unwrap();
+
+        assert_eq!(vrm.nodes[0].morph_target_count, Some(3));
+        assert_eq!(
+            vrm.warnings,
+            vec![VrmLoadWarning::MorphTargetIndexOutOfRange {
+                expression: "happy".to_string(),
+                node: 0,
+                index: 7,
+                count: 3,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_morph_target_bind_weight_clamped() {
+        let json = br#"{
+            "asset": {"version": "2.0"},
+            "extensions": {"VRMC_vrm": {
+                "specVersion": "1.0",
+                "meta": {"name": "Overdriven"},
+                "expressions": {"preset": {
+                    "angry": {"morphTargetBinds": [
+                        {"node": 0, "index": 1, "weight": 5.0},
+                        {"node": 0, "index": 2, "weight": 0.5}
+                    ]}
+                }}
+            }}
+        }"#;
+
+        let vrm = parse_vrm_bytes(json, Handle::default()).unwrap();
+
+        let binds = &vrm.expressions["angry"].morph_target_binds;
+        assert_eq!(binds[0].weight, 1.0);
+        assert_eq!(binds[1].weight, 0.5);
+        assert_eq!(
+            vrm.warnings,
+            vec![VrmLoadWarning::MorphTargetBindWeightOutOfRange {
+                expression: "angry".to_string(),
+                node: 0,
+                index: 1,
+                weight: 5.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_node_transforms() {
+        let json = br#"{
+            "asset": {"version": "2.0"},
+            "nodes": [
+                {"name": "Root", "scale": [0.01, 0.01, 0.01], "children": [1]},
+                {"name": "Hips", "translation": [0.0, 90.0, 0.0]},
+                {"name": "Placed", "matrix": [1,0,0,0, 0,1,0,0, 0,0,1,0, 1,2,3,1]}
+            ],
+            "extensions": {"VRMC_vrm": {
+                "specVersion": "1.0",
+                "meta": {"name": "Scaled"},
+                "humanoid": {"humanBones": {"hips": {"node": 1}}}
+            }}
+        }"#;
+
+        let vrm = parse_vrm_bytes(json, Handle::default()).unwrap();
+
+        assert_eq!(vrm.nodes[0].transform.scale, Vec3::splat(0.01));
+        assert_eq!(vrm.nodes[2].transform.translation, Vec3::new(1.0, 2.0, 3.0));
+        assert!((vrm.hips_height().unwrap() - 0.9).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_non_finite_node_transform_warns() {
+        // JSON cannot spell NaN, but values overflowing f32 and zero
+        // quaternions both produce non-finite transforms
+        let json = br#"{
+            "asset": {"version": "2.0"},
+            "nodes": [
+                {"name": "Root", "children": [1, 2]},
+                {"name": "Hips", "translation": [0.0, 1e39, 0.0]},
+                {"name": "Spine", "rotation": [0.0, 0.0, 0.0, 0.0]}
+            ],
+            "extensions": {"VRMC_vrm": {"specVersion": "1.0", "meta": {"name": "Corrupt"}}}
+        }"#;
+
+        let vrm = parse_vrm_bytes(json, Handle::default()).unwrap();
+
+        assert_eq!(
+            vrm.warnings,
+            vec![
+                VrmLoadWarning::NonFiniteNodeTransform { node: 1 },
+                VrmLoadWarning::NonFiniteNodeTransform { node: 2 },
+            ]
+        );
+        assert!(!vrm.nodes[1].transform.is_finite());
+
+        let settings = VrmLoaderSettings {
+            sanitize_non_finite_transforms: true,
+        };
+        let sanitized = parse_vrm_bytes_with_settings(json, Handle::default(), &settings).unwrap();
+        assert_eq!(sanitized.warnings, vrm.warnings);
+        assert_eq!(sanitized.nodes[1].transform, Transform::IDENTITY);
+        assert_eq!(sanitized.nodes[2].transform, Transform::IDENTITY);
+    }
+
+    #[test]
+    fn test_malformed_look_at_keeps_other_sections() {
+        let json = br#"{
+            "asset": {"version": "2.0"},
+            "nodes": [{"name": "Face", "mesh": 0}],
+            "meshes": [{"primitives": [{"targets": [{}]}]}],
+            "extensions": {"VRMC_vrm": {
+                "specVersion": "1.0",
+                "meta": {"name": "Partial"},
+                "expressions": {"preset": {"happy": {"morphTargetBinds": [{"node": 0, "index": 0, "weight": 1.0}]}}},
+                "lookAt": {"type": 42}
+            }}
+        }"#;
+
+        let vrm = parse_vrm_bytes(json, Handle::default()).unwrap();
+
+        assert_eq!(vrm.meta.name, "Partial");
+        assert!(vrm.expressions.contains_key("happy"));
+        assert!(vrm.look_at.is_none());
+        assert!(matches!(
+            vrm.warnings.as_slice(),
+            [VrmLoadWarning::InvalidVrmExtensionSection { section, .. }] if section == "lookAt"
+        ));
+    }
+
+    #[test]
+    fn test_mtoon_materials() {
+        let json = br#"{
+            "asset": {"version": "2.0"},
+            "materials": [
+                {"name": "Skin", "extensions": {"VRMC_materials_mtoon": {"specVersion": "1.0", "shadingShiftFactor": -0.1}}},
+                {"name": "Plain"},
+                {"name": "Broken", "extensions": {"VRMC_materials_mtoon": {"shadingShiftFactor": "high"}}}
+            ],
+            "extensions": {"VRMC_vrm": {"specVersion": "1.0", "meta": {"name": "Toon"}}}
+        }"#;
+
+        let vrm = parse_vrm_bytes(json, Handle::default()).unwrap();
+
+        assert_eq!(vrm.mtoon_materials.len(), 3);
+        assert_eq!(
+            vrm.mtoon_materials[0]
+                .as_ref()
+                .unwrap()
+                .shading_shift_factor,
+            -0.1
+        );
+        assert!(vrm.mtoon_materials[1].is_none());
+        assert!(vrm.mtoon_materials[2].is_none());
+        assert!(matches!(
+            vrm.warnings.as_slice(),
+            [VrmLoadWarning::InvalidMToonMaterial { material: 2, .. }]
+        ));
+    }
+
+    #[test]
+    fn test_animation_count() {
+        let vrm = parse_vrm_bytes(MINIMAL_VRM_JSON, Handle::default()).unwrap();
+        assert!(!vrm.has_animations());
+        assert!(vrm.warnings.is_empty());
+
+        let json = br#"{
+            "asset": {"version": "2.0"},
+            "animations": [{"channels": [], "samplers": []}, {"channels": [], "samplers": []}],
+            "extensions": {"VRMC_vrm": {"specVersion": "1.0", "meta": {"name": "Animated"}}}
+        }"#;
+        let vrm = parse_vrm_bytes(json, Handle::default()).unwrap();
+        assert!(vrm.has_animations());
+        assert_eq!(vrm.animation_count, 2);
+        assert_eq!(
+            vrm.warnings,
+            vec![VrmLoadWarning::ContainsAnimations { count: 2 }]
+        );
+    }
+
+    #[test]
+    fn test_parse_vrm_metadata_only() {
+        let meta = parse_vrm_metadata_only(&build_glb(MINIMAL_VRM_JSON)).unwrap();
+        assert_eq!(meta.name, "Minimal");
+
+        let result = parse_vrm_metadata_only(b"not a model");
+        assert!(matches!(result, Err(VrmLoadError::Json(_))));
+    }
+
+    #[test]
+    fn test_read_vrm_metadata_only_skips_binary_chunk() {
+        // A BIN chunk header claiming more data than the file holds would fail a full parse
+        let mut glb = build_glb(MINIMAL_VRM_JSON);
+        glb.extend_from_slice(&u32::MAX.to_le_bytes());
+        glb.extend_from_slice(&0x004E4942u32.to_le_bytes());
+
+        let path = std::env::temp_dir().join(format!(
+            "vrm_loader_metadata_only_{}.vrm",
+            std::process::id()
+        ));
+        std::fs::write(&path, &glb).unwrap();
+        let meta = read_vrm_metadata_only(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(meta.unwrap().name, "Minimal");
+    }
+
+    #[test]
+    fn test_parse_vrm_bytes_missing_extension() {
+        let result = parse_vrm_bytes(br#"{"asset": {"version": "2.0"}}"#, Handle::default());
+        assert!(matches!(result, Err(VrmLoadError::MissingVrmExtension)));
+    }
+}
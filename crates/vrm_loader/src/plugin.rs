@@ -30,6 +30,8 @@ impl Plugin for VrmLoaderPlugin {
 /// - Detects when VRM assets finish loading
 /// - Prints metadata to console
 /// - Prints expression information
+/// - Logs which humanoid bone groups are present
+/// - Logs any load warnings
 fn process_loaded_vrm_assets(
     mut events: MessageReader<AssetEvent<VrmAsset>>,
     vrm_assets: Res<Assets<VrmAsset>>,
@@ -40,6 +42,15 @@ fn process_loaded_vrm_assets(
                 // Print metadata to console
                 print_vrm_metadata(&vrm.meta);
                 print_vrm_expressions(&vrm.expressions);
+                info!(
+                    "VRM '{}' tracking capabilities: {:?}",
+                    vrm.meta.name,
+                    vrm.tracking_capabilities()
+                );
+
+                for warning in &vrm.warnings {
+                    warn!("VRM '{}': {}", vrm.meta.name, warning);
+                }
             }
         }
     }
@@ -49,33 +60,94 @@ fn process_loaded_vrm_assets(
 #[derive(Component)]
 pub struct VrmHandle(pub Handle<VrmAsset>);
 
+/// Marker that suppresses the automatic glTF scene spawn for a [`VrmHandle`] entity.
+///
+/// The entity still receives its [`VrmEntity`] component once the model is
+/// loaded, but no `SceneRoot` is inserted, leaving the scene spawn (parent,
+/// transform, render layers) to the app.
+#[derive(Component, Debug, Default)]
+pub struct ManualVrmSpawn;
+
 /// System that spawns VRM entities in the scene.
 ///
 /// This system looks for unspawned VRM assets and creates entities for them.
-/// It also ensures the underlying glTF scene is spawned.
+/// It also ensures the underlying glTF scene is spawned, unless the entity is
+/// marked with [`ManualVrmSpawn`].
 fn spawn_vrm_entities(
     mut commands: Commands,
     vrm_assets: Res<Assets<VrmAsset>>,
     gltf_assets: Res<Assets<Gltf>>,
-    query: Query<(Entity, &VrmHandle), Without<VrmEntity>>,
+    query: Query<(Entity, &VrmHandle, Has<ManualVrmSpawn>), Without<VrmEntity>>,
 ) {
-    for (entity, vrm_handle) in query.iter() {
+    for (entity, vrm_handle, manual_spawn) in query.iter() {
         if let Some(vrm) = vrm_assets.get(&vrm_handle.0) {
             // Check if the glTF is loaded
             if let Some(gltf) = gltf_assets.get(&vrm.gltf) {
-                // Add VrmEntity component
-                commands.entity(entity).insert(VrmEntity {
-                    vrm: vrm_handle.0.clone(),
-                    name: vrm.meta.name.clone(),
-                });
-
-                // Spawn the default glTF scene
-                if let Some(scene) = gltf.scenes.first() {
-                    commands.entity(entity).insert(SceneRoot(scene.clone()));
-                }
+                spawn_vrm_entity(
+                    &mut commands.entity(entity),
+                    vrm_handle.0.clone(),
+                    &vrm.meta.name,
+                    gltf.scenes.first().cloned(),
+                    manual_spawn,
+                );
 
                 info!("Spawned VRM entity: {}", vrm.meta.name);
             }
         }
     }
 }
+
+/// Mark an entity as a spawned VRM and attach its glTF scene, if any.
+///
+/// The scene is skipped for entities marked with [`ManualVrmSpawn`].
+fn spawn_vrm_entity(
+    entity: &mut EntityCommands,
+    vrm: Handle<VrmAsset>,
+    name: &str,
+    scene: Option<Handle<Scene>>,
+    manual_spawn: bool,
+) {
+    // Add VrmEntity component
+    entity.insert(VrmEntity {
+        vrm,
+        name: name.to_string(),
+    });
+
+    // Spawn the default glTF scene
+    if let Some(scene) = scene.filter(|_| !manual_spawn) {
+        entity.insert(SceneRoot(scene));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spawn_vrm_entity_scene() {
+        let mut world = World::new();
+        let automatic = world.spawn_empty().id();
+        let manual = world.spawn(ManualVrmSpawn).id();
+
+        let mut commands = world.commands();
+        spawn_vrm_entity(
+            &mut commands.entity(automatic),
+            Handle::default(),
+            "Auto",
+            Some(Handle::default()),
+            false,
+        );
+        spawn_vrm_entity(
+            &mut commands.entity(manual),
+            Handle::default(),
+            "Manual",
+            Some(Handle::default()),
+            true,
+        );
+        world.flush();
+
+        assert!(world.get::<SceneRoot>(automatic).is_some());
+        assert_eq!(world.get::<VrmEntity>(manual).unwrap().name, "Manual");
+        assert!(world.get::<SceneRoot>(manual).is_none());
+    }
+}
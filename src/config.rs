@@ -1,6 +1,10 @@
+use bevy::prelude::Resource;
 use directories::ProjectDirs;
+use expression_adapter::ArkitAdapterConfig;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
+use vrm_loader::read_vrm_metadata_only;
 
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +16,213 @@ pub struct AppConfig {
     /// Video device index to use for face tracking (default: 0)
     #[serde(default)]
     pub camera_device_id: u32,
+    /// Model units per meter for position tracking (estimated from the hip height if unset)
+    #[serde(default)]
+    pub model_scale: Option<f32>,
+    /// Pause applying tracking while the app window is unfocused (default: false)
+    #[serde(default)]
+    pub pause_when_unfocused: bool,
+    /// How to launch the Python tracker process
+    #[serde(default)]
+    pub tracker: TrackerConfig,
+    /// Tuning options for mapping tracker blendshapes to VRM expressions
+    #[serde(default)]
+    pub expressions: ArkitAdapterConfig,
+    /// Options for applying expression weights to the model's morph targets
+    #[serde(default)]
+    pub expression_apply: ExpressionApplyConfig,
+    /// Named snapshots of the tuning options, switchable at runtime
+    #[serde(default)]
+    pub presets: ConfigPresets,
+    /// Where to stream each applied frame as a JSON line for external tools
+    #[serde(default)]
+    pub output: PipelineOutputConfig,
+}
+
+/// Summary of a VRM model file found in the user VRM directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct VrmModelInfo {
+    /// Path to the model file
+    pub path: PathBuf,
+    /// Model name from the VRM metadata
+    pub name: String,
+    /// Model authors from the VRM metadata
+    pub authors: Vec<String>,
+    /// glTF image index of the model's thumbnail, if any
+    pub thumbnail_image: Option<usize>,
+}
+
+/// Launch options for the Python tracker process
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackerConfig {
+    /// Python interpreter (default: `PYTHON_BIN` if set, otherwise `python3`)
+    pub python_path: Option<PathBuf>,
+    /// Tracker script (default: `tools/mediapipe_tracker.py`)
+    pub script_path: Option<PathBuf>,
+    /// Which landmarks the tracker detects
+    pub mode: TrackerMode,
+    /// Working directory for the tracker (default: the app's working directory)
+    pub working_dir: Option<PathBuf>,
+    /// Extra environment variables, e.g. `PYTHONPATH` or `VIRTUAL_ENV`
+    pub env: BTreeMap<String, String>,
+    /// Extra arguments appended after the built-in ones
+    pub extra_args: Vec<String>,
+}
+
+impl TrackerConfig {
+    /// Arguments for the tracker script: `--camera`, `--mode` when not the default, then `extra_args`
+    pub fn script_args(&self, camera_device_id: u32) -> Vec<String> {
+        let mut args = vec!["--camera".to_string(), camera_device_id.to_string()];
+        // Only pass a non-default mode, so custom scripts without `--mode` still run
+        if self.mode != TrackerMode::default() {
+            args.extend(["--mode".to_string(), self.mode.as_arg().to_string()]);
+        }
+        args.extend(self.extra_args.iter().cloned());
+        args
+    }
+}
+
+/// Which landmarks the tracker detects, passed to it as `--mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackerMode {
+    /// Face blendshapes and pose landmarks
+    #[default]
+    Full,
+    /// Face blendshapes only, skipping the pose model
+    Face,
+}
+
+impl TrackerMode {
+    /// Value of the tracker's `--mode` argument
+    pub fn as_arg(self) -> &'static str {
+        match self {
+            TrackerMode::Full => "full",
+            TrackerMode::Face => "face",
+        }
+    }
+}
+
+/// Options for applying expression weights to the model's morph targets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExpressionApplyConfig {
+    /// Per-expression overrides keyed by VRM expression name (e.g. "happy")
+    pub overrides: HashMap<String, ExpressionOverride>,
+    /// Maximum change of any expression weight per second (unlimited if unset)
+    ///
+    /// Caps how fast a weight can move so a detection glitch jumping from 0.0
+    /// to 1.0 in one frame does not pop the face.
+    pub max_weight_rate: Option<f32>,
+    /// How contributions of several expressions to the same morph target combine
+    pub morph_combine: MorphCombine,
+    /// Multiplier applied to every expression weight (0.0 = neutral, 1.0 = full)
+    ///
+    /// Values above 1.0 exaggerate expressions; each morph target is still
+    /// clamped to 1.0.
+    pub master_expression_intensity: f32,
+}
+
+impl Default for ExpressionApplyConfig {
+    fn default() -> Self {
+        Self {
+            overrides: HashMap::new(),
+            max_weight_rate: None,
+            morph_combine: MorphCombine::default(),
+            master_expression_intensity: 1.0,
+        }
+    }
+}
+
+/// Policy for combining several expressions that bind the same morph target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MorphCombine {
+    /// Sum all contributions, clamped to 1.0
+    #[default]
+    Add,
+    /// Keep the strongest contribution
+    Max,
+}
+
+impl MorphCombine {
+    /// Fold one more contribution into a morph target's accumulated weight
+    pub fn combine(self, accumulated: f32, contribution: f32) -> f32 {
+        match self {
+            MorphCombine::Add => accumulated + contribution,
+            MorphCombine::Max => accumulated.max(contribution),
+        }
+    }
+}
+
+/// Destination for the JSON lines pipeline output
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "sink", rename_all = "lowercase")]
+pub enum PipelineOutputConfig {
+    /// No output
+    #[default]
+    None,
+    /// Connect to a TCP listener at `address` (e.g. "127.0.0.1:9000")
+    Tcp { address: String },
+}
+
+/// Snapshot of the tracking tuning options
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackingPreset {
+    /// Expression adapter tuning, as in [`AppConfig::expressions`]
+    pub expressions: ArkitAdapterConfig,
+    /// Expression apply options, as in [`AppConfig::expression_apply`]
+    pub expression_apply: ExpressionApplyConfig,
+}
+
+/// Named tracking presets, for comparing tuning options live
+///
+/// Stored in the config file as `[presets.<name>]` tables.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ConfigPresets {
+    presets: BTreeMap<String, TrackingPreset>,
+}
+
+impl ConfigPresets {
+    /// Store the current tuning options of `config` as `name`, replacing any
+    /// preset with the same name
+    pub fn capture(&mut self, name: &str, config: &AppConfig) {
+        self.presets.insert(
+            name.to_string(),
+            TrackingPreset {
+                expressions: config.expressions.clone(),
+                expression_apply: config.expression_apply.clone(),
+            },
+        );
+    }
+
+    /// Overwrite the tuning options of `config` with the preset `name`
+    ///
+    /// Returns `false` and leaves `config` untouched if there is no such preset.
+    pub fn apply(&self, name: &str, config: &mut AppConfig) -> bool {
+        let Some(preset) = self.presets.get(name) else {
+            return false;
+        };
+        config.expressions = preset.expressions.clone();
+        config.expression_apply = preset.expression_apply.clone();
+        true
+    }
+
+    /// Preset names in sorted order
+    pub fn list(&self) -> Vec<&str> {
+        self.presets.keys().map(String::as_str).collect()
+    }
+}
+
+/// Per-expression options for models whose morphs need correcting
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExpressionOverride {
+    /// Apply `1.0 - weight` instead of the weight, for morphs authored backwards
+    pub invert: bool,
 }
 
 impl Default for AppConfig {
@@ -21,6 +232,13 @@ impl Default for AppConfig {
             user_vrm_dir,
             default_vrm_model: "model.vrm".to_string(),
             camera_device_id: 0,
+            pause_when_unfocused: false,
+            model_scale: None,
+            tracker: TrackerConfig::default(),
+            expressions: ArkitAdapterConfig::default(),
+            expression_apply: ExpressionApplyConfig::default(),
+            presets: ConfigPresets::default(),
+            output: PipelineOutputConfig::default(),
         }
     }
 }
@@ -62,6 +280,49 @@ impl AppConfig {
         std::fs::create_dir_all(&self.user_vrm_dir)?;
         Ok(())
     }
+
+    /// List the `.vrm` files in the user VRM directory with their metadata
+    ///
+    /// Only the metadata is parsed, so this is cheap enough for a model picker.
+    /// Files that cannot be read or are not valid VRM models are skipped with a
+    /// warning. Models are sorted by path.
+    pub fn list_models(&self) -> Vec<VrmModelInfo> {
+        let entries = match std::fs::read_dir(&self.user_vrm_dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!(
+                    "Warning: Failed to read user VRM directory {}: {e}",
+                    self.user_vrm_dir.display()
+                );
+                return Vec::new();
+            }
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("vrm"))
+            })
+            .collect();
+        paths.sort();
+
+        paths
+            .into_iter()
+            .filter_map(|path| match read_vrm_metadata_only(&path) {
+                Ok(meta) => Some(VrmModelInfo {
+                    name: meta.name,
+                    authors: meta.authors,
+                    thumbnail_image: meta.thumbnail_image,
+                    path,
+                }),
+                Err(e) => {
+                    eprintln!("Warning: Skipping {}: {e}", path.display());
+                    None
+                }
+            })
+            .collect()
+    }
 }
 
 /// Get the user's VRM models directory
@@ -93,5 +354,171 @@ mod tests {
         assert_eq!(config.default_vrm_model, "model.vrm");
         assert!(!config.user_vrm_dir.as_os_str().is_empty());
         assert_eq!(config.camera_device_id, 0);
+        assert!(!config.pause_when_unfocused);
+        assert_eq!(config.expressions, ArkitAdapterConfig::default());
+    }
+
+    #[test]
+    fn test_tracker_script_args_pass_mode_only_when_not_default() {
+        let mut tracker = TrackerConfig {
+            extra_args: vec!["--verbose".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(tracker.script_args(1), ["--camera", "1", "--verbose"]);
+
+        tracker.mode = TrackerMode::Face;
+        assert_eq!(
+            tracker.script_args(1),
+            ["--camera", "1", "--mode", "face", "--verbose"]
+        );
+    }
+
+    #[test]
+    fn test_list_models() {
+        let dir = std::env::temp_dir().join(format!(
+            "vrm1-face-tracking-list-models-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let model = |name: &str| {
+            format!(
+                r#"{{"asset": {{"version": "2.0"}}, "extensions": {{"VRMC_vrm": {{"specVersion": "1.0", "meta": {{"name": "{name}", "authors": ["Author"], "thumbnailImage": 0}}}}}}}}"#
+            )
+        };
+        std::fs::write(dir.join("b.vrm"), model("Second")).unwrap();
+        std::fs::write(dir.join("a.vrm"), model("First")).unwrap();
+        std::fs::write(dir.join("junk.vrm"), "not a model").unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let config = AppConfig {
+            user_vrm_dir: dir.clone(),
+            ..AppConfig::default()
+        };
+        let models = config.list_models();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].name, "First");
+        assert_eq!(models[0].path, dir.join("a.vrm"));
+        assert_eq!(models[0].authors, vec!["Author".to_string()]);
+        assert_eq!(models[0].thumbnail_image, Some(0));
+        assert_eq!(models[1].name, "Second");
+    }
+
+    #[test]
+    fn test_config_expressions_section() {
+        // Configs written before the section existed still load
+        let config: AppConfig =
+            toml::from_str("user_vrm_dir = \"models\"\ndefault_vrm_model = \"model.vrm\"\n")
+                .unwrap();
+        assert_eq!(config.expressions, ArkitAdapterConfig::default());
+
+        let config: AppConfig = toml::from_str(
+            "user_vrm_dir = \"models\"\ndefault_vrm_model = \"model.vrm\"\n\n[expressions]\nblink_suppression_during_speech = 0.4\n",
+        )
+        .unwrap();
+        assert_eq!(config.expressions.blink_suppression_during_speech, 0.4);
+    }
+
+    #[test]
+    fn test_config_expression_overrides() {
+        let config: AppConfig = toml::from_str(
+            "user_vrm_dir = \"models\"\ndefault_vrm_model = \"model.vrm\"\n\n[expression_apply.overrides.happy]\ninvert = true\n",
+        )
+        .unwrap();
+        assert!(config.expression_apply.overrides["happy"].invert);
+        assert!(!config.expression_apply.overrides.contains_key("sad"));
+        assert_eq!(config.expression_apply.max_weight_rate, None);
+    }
+
+    #[test]
+    fn test_config_tracker_section() {
+        let config: AppConfig = toml::from_str(
+            "user_vrm_dir = \"models\"\ndefault_vrm_model = \"model.vrm\"\n\n[tracker]\nworking_dir = \"/opt/tracker\"\nextra_args = [\"--verbose\"]\n\n[tracker.env]\nPYTHONPATH = \"/opt/tracker/lib\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.tracker.working_dir,
+            Some(PathBuf::from("/opt/tracker"))
+        );
+        assert_eq!(config.tracker.extra_args, vec!["--verbose".to_string()]);
+        assert_eq!(config.tracker.env["PYTHONPATH"], "/opt/tracker/lib");
+    }
+
+    #[test]
+    fn test_config_tracker_round_trip() {
+        let config = AppConfig {
+            tracker: TrackerConfig {
+                python_path: Some(PathBuf::from("/opt/venv/bin/python")),
+                script_path: Some(PathBuf::from("/opt/tracker/my_tracker.py")),
+                mode: TrackerMode::Face,
+                extra_args: vec!["--verbose".to_string()],
+                ..TrackerConfig::default()
+            },
+            ..AppConfig::default()
+        };
+
+        let content = toml::to_string_pretty(&config).unwrap();
+        assert!(content.contains("mode = \"face\""));
+        let reloaded: AppConfig = toml::from_str(&content).unwrap();
+        assert_eq!(reloaded.tracker, config.tracker);
+
+        // The section's new fields default when absent
+        let config: AppConfig = toml::from_str(
+            "user_vrm_dir = \"models\"\ndefault_vrm_model = \"model.vrm\"\n\n[tracker]\nextra_args = []\n",
+        )
+        .unwrap();
+        assert_eq!(config.tracker.python_path, None);
+        assert_eq!(config.tracker.mode, TrackerMode::Full);
+    }
+
+    #[test]
+    fn test_config_presets_capture_and_apply() {
+        let mut config = AppConfig::default();
+        let mut presets = ConfigPresets::default();
+
+        config.expression_apply.max_weight_rate = Some(4.0);
+        presets.capture("smooth", &config);
+        config.expression_apply.max_weight_rate = None;
+        config.expressions.blink_suppression_during_speech = 0.5;
+        presets.capture("raw", &config);
+        assert_eq!(presets.list(), vec!["raw", "smooth"]);
+
+        assert!(presets.apply("smooth", &mut config));
+        assert_eq!(config.expression_apply.max_weight_rate, Some(4.0));
+        assert_eq!(config.expressions.blink_suppression_during_speech, 0.0);
+
+        assert!(presets.apply("raw", &mut config));
+        assert_eq!(config.expression_apply.max_weight_rate, None);
+        assert_eq!(config.expressions.blink_suppression_during_speech, 0.5);
+
+        assert!(!presets.apply("missing", &mut config));
+        assert_eq!(config.expressions.blink_suppression_during_speech, 0.5);
+
+        // Presets round-trip through the config file
+        config.presets = presets.clone();
+        let reloaded: AppConfig =
+            toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(reloaded.presets, presets);
+    }
+
+    #[test]
+    fn test_config_output_section() {
+        let config: AppConfig =
+            toml::from_str("user_vrm_dir = \"models\"\ndefault_vrm_model = \"model.vrm\"\n")
+                .unwrap();
+        assert_eq!(config.output, PipelineOutputConfig::None);
+
+        let config: AppConfig = toml::from_str(
+            "user_vrm_dir = \"models\"\ndefault_vrm_model = \"model.vrm\"\n\n[output]\nsink = \"tcp\"\naddress = \"127.0.0.1:9000\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.output,
+            PipelineOutputConfig::Tcp {
+                address: "127.0.0.1:9000".to_string()
+            }
+        );
     }
 }
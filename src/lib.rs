@@ -0,0 +1,2582 @@
+//! Bevy app that drives a VRM 1.0 avatar from MediaPipe face and pose tracking.
+//!
+//! [`FaceTrackingAppPlugin`] assembles the whole app; `src/main.rs` only loads
+//! the configuration and runs it.
+use bevy::asset::io::{AssetSource, AssetSourceId};
+use bevy::camera::primitives::Aabb;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use expression_adapter::{
+    ArkitToVrmAdapter, BlendshapeToExpression, ExpressionGroup, VrmExpression, VrmExpressionPreset,
+};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tracker_ipc::{
+    CameraSelector, DetectedBlendshapeKeys, FrameGapCounter, TrackerCommand, TrackerControl,
+    TrackerFrame, send_control, spawn_tracker_with,
+};
+use vrm_loader::{VrmAsset, VrmHandle, VrmLoaderPlugin};
+
+pub mod config;
+pub mod pipeline_output;
+use config::{AppConfig, ConfigPresets, ExpressionApplyConfig, MorphCombine, PipelineOutputConfig};
+use pipeline_output::{PipelineFrame, PipelineOutputSink, TcpSink};
+
+#[derive(Resource)]
+struct TrackerReceiver {
+    rx: crossbeam_channel::Receiver<TrackerFrame>,
+}
+
+#[derive(Resource)]
+struct TrackerProcess {
+    #[allow(dead_code)]
+    child: std::process::Child,
+}
+
+/// Resource for sending control commands to the tracker process.
+#[derive(Resource)]
+struct TrackerControlChannel {
+    stdin: Option<std::process::ChildStdin>,
+    /// Video device index the tracker last reported capturing from
+    camera_device_id: u32,
+}
+
+#[derive(Resource, Default)]
+struct VrmModelPath {
+    path: Option<PathBuf>,
+}
+
+#[derive(Resource)]
+struct FileDialogChannel {
+    tx: Arc<Mutex<crossbeam_channel::Sender<Option<PathBuf>>>>,
+    rx: crossbeam_channel::Receiver<Option<PathBuf>>,
+}
+
+#[derive(Resource)]
+struct Config {
+    inner: AppConfig,
+}
+
+#[derive(Component)]
+struct CurrentVrmEntity;
+
+/// Marker for a VRM entity the camera has already been framed on.
+#[derive(Component)]
+struct AutoFramed;
+
+/// Component that stores the VRM expression to morph target mapping for a mesh entity.
+/// This is attached to mesh entities after a VRM is loaded to enable applying expressions.
+#[derive(Component, Clone)]
+struct VrmExpressionMap {
+    /// Map from VRM expression name (e.g., "happy", "blink") to morph target indices and weights
+    /// The Vec contains tuples of (morph_target_index, base_weight)
+    expression_to_morphs: HashMap<String, Vec<(usize, f32)>>,
+}
+
+/// Resource recording which blendshapes the tracker actually reports.
+#[derive(Resource, Default)]
+pub struct TrackerCapabilities {
+    blendshapes: DetectedBlendshapeKeys,
+}
+
+impl TrackerCapabilities {
+    /// Blendshape keys seen over the tracker's first face frames, e.g. for a
+    /// UI to hide expressions that can never fire
+    pub fn blendshapes(&self) -> &DetectedBlendshapeKeys {
+        &self.blendshapes
+    }
+}
+
+/// Resource with diagnostics about the tracker's frame stream.
+#[derive(Resource, Default)]
+pub struct TrackerDiagnostics {
+    /// Frames dropped between the tracker and the app, from sequence numbers
+    frame_gaps: FrameGapCounter,
+}
+
+impl TrackerDiagnostics {
+    /// Frames missing from the tracker's sequence so far
+    pub fn dropped_frames(&self) -> u64 {
+        self.frame_gaps.dropped()
+    }
+
+    /// Fraction of the tracker's frames that were dropped (0.0 before any frames)
+    pub fn drop_rate(&self) -> f32 {
+        self.frame_gaps.drop_rate()
+    }
+}
+
+/// Resource holding the expression adapter and its calibration state.
+#[derive(Resource)]
+struct ExpressionAdapter {
+    adapter: ArkitToVrmAdapter,
+    /// Whether the next face frame should calibrate the resting mouth (`C`)
+    calibrate_mouth_pending: bool,
+    /// Whether the next face frame should calibrate every blendshape (`N`)
+    calibrate_neutral_pending: bool,
+}
+
+/// Message carrying one frame received from the tracker process.
+#[derive(Message)]
+struct TrackerFrameReceived(TrackerFrame);
+
+/// Resource that stores the current VRM expression weights from face tracking.
+#[derive(Resource, Default)]
+struct CurrentExpressions {
+    expressions: Vec<VrmExpression>,
+    /// Weights for custom expressions the loaded model defines (e.g. `eyeWide`)
+    custom: Vec<(&'static str, f32)>,
+    /// Tracker timestamp of the frame the expressions came from
+    ts: f64,
+}
+
+/// Message sent every frame expressions are applied, for systems that react
+/// to the current facial state (e.g. effects on a big smile).
+#[derive(Message, Debug, Clone)]
+pub struct ExpressionSnapshot {
+    /// Applied weights keyed by expression name. Presets are after rate
+    /// limiting, the enable mask, the master intensity and inversion; custom
+    /// expressions only get the master intensity and inversion
+    pub weights: HashMap<String, f32>,
+}
+
+/// Resource that records expression names the tracker emitted but the loaded
+/// model has no morph target bindings for.
+///
+/// Names are deduplicated so each missing expression is reported only once per
+/// loaded model. The set is cleared whenever a new model is loaded.
+#[derive(Resource, Default)]
+pub struct UnmappedExpressions {
+    names: BTreeSet<String>,
+}
+
+impl UnmappedExpressions {
+    /// Expression names the loaded model cannot show, in sorted order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(String::as_str)
+    }
+
+    /// Record an unmapped expression name.
+    ///
+    /// Returns `true` if the name had not been recorded before.
+    fn record(&mut self, name: &str) -> bool {
+        if self.names.contains(name) {
+            return false;
+        }
+        self.names.insert(name.to_string());
+        true
+    }
+
+    fn clear(&mut self) {
+        self.names.clear();
+    }
+}
+
+/// Resource holding the expression weights applied on the previous frame.
+///
+/// Used to cap how fast each weight may change when
+/// `expression_apply.max_weight_rate` is set.
+#[derive(Resource, Default)]
+struct AppliedExpressionWeights {
+    weights: HashMap<VrmExpressionPreset, f32>,
+}
+
+impl AppliedExpressionWeights {
+    /// Move each weight towards its target by at most `max_rate * dt`.
+    ///
+    /// Expressions absent from `expressions` move towards 0.0 so they fade out
+    /// instead of dropping. Returns the limited expressions to apply.
+    fn limit(
+        &mut self,
+        expressions: &[VrmExpression],
+        max_rate: f32,
+        dt: f32,
+    ) -> Vec<VrmExpression> {
+        let max_step = max_rate.max(0.0) * dt;
+
+        let mut targets: HashMap<VrmExpressionPreset, f32> =
+            self.weights.keys().map(|preset| (*preset, 0.0)).collect();
+        for expr in expressions {
+            targets.insert(expr.preset, expr.weight);
+        }
+
+        let mut limited = Vec::with_capacity(targets.len());
+        for (preset, target) in targets {
+            let previous = self.weights.get(&preset).copied().unwrap_or(0.0);
+            let weight = previous + (target - previous).clamp(-max_step, max_step);
+            if weight > 0.0 {
+                self.weights.insert(preset, weight);
+                limited.push(VrmExpression::new(preset, weight));
+            } else {
+                self.weights.remove(&preset);
+            }
+        }
+
+        limited
+    }
+}
+
+/// Number of applied frames kept in [`FrameHistory`] (about five seconds at 60 fps).
+const FRAME_HISTORY_CAPACITY: usize = 300;
+
+/// Tracking state applied to the avatar on one app frame.
+#[derive(Debug, Clone)]
+struct FrameSnapshot {
+    /// App time in seconds when the snapshot was recorded
+    time_secs: f32,
+    expressions: Vec<VrmExpression>,
+    custom: Vec<(&'static str, f32)>,
+    body_midpoint: Option<Vec3>,
+}
+
+/// Resource keeping the most recent applied frames for scrubbing back through
+/// a glitch.
+///
+/// Holds at most `capacity` snapshots, dropping the oldest. While scrubbing,
+/// recording stops and the selected snapshot is re-applied instead of live
+/// tracking.
+#[derive(Resource)]
+struct FrameHistory {
+    snapshots: VecDeque<FrameSnapshot>,
+    capacity: usize,
+    /// Index of the snapshot being re-applied, if scrubbing
+    scrub_index: Option<usize>,
+}
+
+impl Default for FrameHistory {
+    fn default() -> Self {
+        Self::new(FRAME_HISTORY_CAPACITY)
+    }
+}
+
+impl FrameHistory {
+    fn new(capacity: usize) -> Self {
+        Self {
+            snapshots: VecDeque::with_capacity(capacity),
+            capacity,
+            scrub_index: None,
+        }
+    }
+
+    /// Append a snapshot, dropping the oldest one when full.
+    fn record(&mut self, snapshot: FrameSnapshot) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+    }
+
+    /// Overwrite the current tracking state with the snapshot at `index`
+    /// (0 = oldest).
+    ///
+    /// Returns `false` if there is no such snapshot.
+    fn restore(
+        &self,
+        index: usize,
+        current_expressions: &mut CurrentExpressions,
+        shoulder_pos: &mut CurrentShoulderPosition,
+    ) -> bool {
+        let Some(snapshot) = self.snapshots.get(index) else {
+            return false;
+        };
+        current_expressions.expressions = snapshot.expressions.clone();
+        current_expressions.custom = snapshot.custom.clone();
+        shoulder_pos.midpoint = snapshot.body_midpoint;
+        true
+    }
+}
+
+/// Resource holding the sink applied frames are streamed to, if configured.
+#[derive(Resource, Default)]
+struct PipelineOutput {
+    sink: Option<Box<dyn PipelineOutputSink>>,
+}
+
+/// Seconds for a disabled expression to fade out, or a re-enabled one to fade in.
+const EXPRESSION_TOGGLE_FADE_SECS: f32 = 0.25;
+
+/// Resource with runtime on/off switches for expression groups and presets.
+///
+/// Disabled expressions ease to zero over `EXPRESSION_TOGGLE_FADE_SECS`
+/// instead of cutting out, e.g. to keep the avatar looking at the camera.
+#[derive(Resource, Default)]
+pub struct ExpressionEnableMask {
+    disabled_groups: HashSet<ExpressionGroup>,
+    disabled_presets: HashSet<VrmExpressionPreset>,
+    /// Current scale of presets that are fading or off (absent = fully on)
+    scales: HashMap<VrmExpressionPreset, f32>,
+}
+
+impl ExpressionEnableMask {
+    fn is_enabled(&self, preset: VrmExpressionPreset) -> bool {
+        !self.disabled_presets.contains(&preset) && !self.disabled_groups.contains(&preset.group())
+    }
+
+    /// Flip a group on or off, returning whether it is now enabled.
+    pub fn toggle_group(&mut self, group: ExpressionGroup) -> bool {
+        if !self.disabled_groups.remove(&group) {
+            self.disabled_groups.insert(group);
+            return false;
+        }
+        true
+    }
+
+    /// Flip a single preset on or off, returning whether it is now switched on.
+    ///
+    /// A preset switched on still stays off while its group is disabled.
+    pub fn toggle_preset(&mut self, preset: VrmExpressionPreset) -> bool {
+        if !self.disabled_presets.remove(&preset) {
+            self.disabled_presets.insert(preset);
+            return false;
+        }
+        true
+    }
+
+    /// Advance the fades by `dt` seconds and scale the expressions by them.
+    fn apply(&mut self, expressions: &[VrmExpression], dt: f32) -> Vec<VrmExpression> {
+        let max_step = dt / EXPRESSION_TOGGLE_FADE_SECS;
+        for preset in VrmExpressionPreset::ALL {
+            let target = if self.is_enabled(preset) { 1.0 } else { 0.0 };
+            let scale = self.scales.get(&preset).copied().unwrap_or(1.0);
+            let scale = scale + (target - scale).clamp(-max_step, max_step);
+            if scale >= 1.0 {
+                self.scales.remove(&preset);
+            } else {
+                self.scales.insert(preset, scale);
+            }
+        }
+
+        expressions
+            .iter()
+            .filter_map(|expr| {
+                let scale = self.scales.get(&expr.preset).copied().unwrap_or(1.0);
+                (scale > 0.0).then(|| VrmExpression::new(expr.preset, expr.weight * scale))
+            })
+            .collect()
+    }
+}
+
+/// Resource recording whether tracking is paused because the window is unfocused.
+///
+/// While paused, tracker frames are still drained (so they do not back up) and
+/// count towards [`TrackingQuality`], but are not converted or applied to the
+/// model.
+#[derive(Resource, Default)]
+struct TrackingPaused {
+    paused: bool,
+}
+
+/// Resource summarising overall tracking health as a single 0.0-1.0 score.
+///
+/// The per-frame inputs are recorded as frames arrive; the score is
+/// recomputed every app frame so it decays while no frames arrive.
+#[derive(Resource, Default)]
+pub struct TrackingQuality {
+    /// Combined tracking quality (0.0 = lost, 1.0 = fully tracked)
+    score: f32,
+    /// Mean visibility of the key upper body pose landmarks in the last frame
+    pose_confidence: f32,
+    /// Whether the last frame contained face blendshapes
+    face_present: bool,
+    /// App time in seconds when the last tracker frame arrived
+    last_frame_secs: Option<f32>,
+}
+
+impl TrackingQuality {
+    /// Combined tracking quality (0.0 = lost, 1.0 = fully tracked), e.g. for
+    /// stream overlays or falling back to an idle pose
+    pub fn score(&self) -> f32 {
+        self.score
+    }
+}
+
+/// Frames younger than this count as fully fresh for [`compute_tracking_quality`].
+const TRACKING_FRESH_SECS: f32 = 0.25;
+/// Age at which a frame no longer contributes to [`compute_tracking_quality`].
+const TRACKING_STALE_SECS: f32 = 1.0;
+/// Score below which tracking is reported as degraded.
+const TRACKING_QUALITY_WARN: f32 = 0.5;
+
+/// Combine pose confidence, face presence and frame age into a 0.0-1.0 score.
+///
+/// Pose confidence and face presence contribute equally. The result is then
+/// scaled by freshness: full for frames up to `TRACKING_FRESH_SECS` old,
+/// falling linearly to zero at `TRACKING_STALE_SECS`.
+fn compute_tracking_quality(pose_confidence: f32, face_present: bool, frame_age_secs: f32) -> f32 {
+    let face = if face_present { 1.0 } else { 0.0 };
+    let base = 0.5 * pose_confidence.clamp(0.0, 1.0) + 0.5 * face;
+
+    let freshness = 1.0
+        - ((frame_age_secs - TRACKING_FRESH_SECS) / (TRACKING_STALE_SECS - TRACKING_FRESH_SECS))
+            .clamp(0.0, 1.0);
+
+    base * freshness
+}
+
+/// Resource that stores the body position derived from shoulder world landmarks.
+///
+/// The midpoint of the two shoulder world landmarks is used to translate the
+/// VRM root entity so that the model tracks the subject's real-world torso
+/// movement.
+#[derive(Resource, Default)]
+struct CurrentShoulderPosition {
+    /// World-space midpoint of the two shoulders in MediaPipe coordinates.
+    /// Origin is at the hip centre; Y is up; X is to the person's right;
+    /// Z is toward the camera.  Units are meters.
+    midpoint: Option<Vec3>,
+}
+
+// Key upper body landmark indices and names for logging
+const KEY_POSE_LANDMARKS: [usize; 7] = [0, 11, 12, 13, 14, 15, 16];
+const KEY_POSE_LANDMARK_NAMES: [&str; 7] = [
+    "nose",
+    "left_shoulder",
+    "right_shoulder",
+    "left_elbow",
+    "right_elbow",
+    "left_wrist",
+    "right_wrist",
+];
+
+/// Index of the left shoulder in MediaPipe's 33-landmark pose array
+const LEFT_SHOULDER_IDX: usize = 11;
+/// Index of the right shoulder in MediaPipe's 33-landmark pose array
+const RIGHT_SHOULDER_IDX: usize = 12;
+/// Minimum visibility score for a shoulder landmark to be considered reliable
+const SHOULDER_VISIBILITY_THRESHOLD: f32 = 0.1;
+/// Hip height (in meters) of a typical adult VRM model, used to estimate
+/// [`ModelScale`].
+const NOMINAL_HIPS_HEIGHT: f32 = 0.9;
+
+/// Resource holding how many model units correspond to one meter for the
+/// loaded model.
+///
+/// Position-based outputs are in meters and are multiplied by this scale.
+/// It is `model_scale` from the config if set, otherwise the model's hip
+/// height relative to `NOMINAL_HIPS_HEIGHT`.
+#[derive(Resource)]
+struct ModelScale {
+    scale: f32,
+    /// Model the scale was computed for
+    source: Option<AssetId<VrmAsset>>,
+}
+
+impl Default for ModelScale {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            source: None,
+        }
+    }
+}
+
+/// Estimate a model's units per meter from its hip height.
+///
+/// Falls back to 1.0 when the hip height is unknown or not positive.
+fn estimate_model_scale(hips_height: Option<f32>) -> f32 {
+    match hips_height {
+        Some(height) if height > 0.0 => height / NOMINAL_HIPS_HEIGHT,
+        _ => 1.0,
+    }
+}
+
+/// System that updates [`ModelScale`] once a newly loaded model's asset is
+/// available.
+fn update_model_scale(
+    config: Res<Config>,
+    vrm_assets: Res<Assets<VrmAsset>>,
+    vrm_query: Query<&VrmHandle, With<CurrentVrmEntity>>,
+    mut model_scale: ResMut<ModelScale>,
+) {
+    for vrm_handle in vrm_query.iter() {
+        let id = vrm_handle.0.id();
+        if model_scale.source == Some(id) {
+            continue;
+        }
+        let Some(vrm_asset) = vrm_assets.get(id) else {
+            continue;
+        };
+
+        model_scale.scale = config
+            .inner
+            .model_scale
+            .unwrap_or_else(|| estimate_model_scale(vrm_asset.hips_height()));
+        model_scale.source = Some(id);
+        println!("Model scale: {:.3} units per meter", model_scale.scale);
+    }
+}
+
+/// Vertical offset (in meters) added to the shoulder world-space Y to obtain the
+/// VRM root (feet) Y in Bevy world space.
+///
+/// MediaPipe world landmarks have their origin at the hip centre, so shoulders
+/// sit at approximately +0.4 m.  A typical VRM model has shoulders at roughly
+/// 1.4 m above its root (feet).  The hip centre is approximately 1.0 m above
+/// the floor, so:
+///
+///   vrm_root_y = hip_floor_offset + shoulder_world_y - shoulder_height_from_feet
+///             ≈ 1.0 + shoulder_world_y - 1.4
+///             = shoulder_world_y - 0.4
+const SHOULDER_Y_OFFSET: f32 = -0.4;
+
+// ---------------------------------------------------------------------------
+// Body movement direction / scale controls
+//
+// Each axis can be independently:
+//   • flipped  – change 1.0 → -1.0 to invert that direction
+//   • scaled   – multiply by a value other than 1.0 to amplify / dampen movement
+//
+// Defaults reproduce a 1-to-1 mapping from MediaPipe world coordinates to
+// Bevy world coordinates with no inversion.
+// ---------------------------------------------------------------------------
+
+/// Sign multiplier for left-right (X) body translation.
+/// 1.0  = natural (MediaPipe X is already "person's right", same as Bevy X)
+/// -1.0 = mirror / flip left↔right
+const BODY_X_SIGN: f32 = -1.0;
+
+/// Scale factor for left-right (X) body translation.
+/// Increase above 1.0 to amplify horizontal movement; decrease toward 0.0 to dampen it.
+const BODY_X_SCALE: f32 = 1.0;
+
+/// Sign multiplier for up-down (Y) body translation.
+/// 1.0  = natural (up in MediaPipe = up in Bevy)
+/// -1.0 = flip up↔down
+const BODY_Y_SIGN: f32 = 1.0;
+
+/// Scale factor for up-down (Y) body translation.
+/// Increase above 1.0 to amplify vertical movement; decrease toward 0.0 to dampen it.
+const BODY_Y_SCALE: f32 = 1.0;
+
+/// Sign multiplier for forward-backward (Z) body translation.
+/// 1.0  = natural (toward-camera in MediaPipe = toward-viewer in Bevy)
+/// -1.0 = flip forward↔backward
+const BODY_Z_SIGN: f32 = -1.0;
+
+/// Scale factor for forward-backward (Z) body translation.
+/// Increase above 1.0 to amplify depth movement; decrease toward 0.0 to dampen it.
+const BODY_Z_SCALE: f32 = 1.0;
+
+/// Name of the asset source that reads from the user VRM directory.
+const USER_VRM_SOURCE: &str = "userdata";
+
+/// Register the `userdata://` asset source reading from `user_vrm_dir`.
+///
+/// [`FaceTrackingAppPlugin`] does this itself when it adds `DefaultPlugins`.
+/// Apps that add their own `AssetPlugin` must call this before adding it,
+/// since asset sources cannot be registered afterwards.
+pub fn register_user_vrm_source(app: &mut App, user_vrm_dir: PathBuf) -> &mut App {
+    app.register_asset_source(
+        AssetSourceId::Name(USER_VRM_SOURCE.into()),
+        AssetSource::build().with_reader(move || {
+            Box::new(bevy::asset::io::file::FileAssetReader::new(
+                user_vrm_dir.clone(),
+            ))
+        }),
+    )
+}
+
+/// Plugin that assembles the whole face tracking app.
+///
+/// Adds `DefaultPlugins` (with the `userdata://` asset source for the user VRM
+/// directory) unless an `AssetPlugin` is already present, the
+/// [`VrmLoaderPlugin`], the tracker process and every tracking system. Apps
+/// that add their own `AssetPlugin` must call [`register_user_vrm_source`]
+/// first, or building the plugin panics. Apps that set up their own plugins
+/// can still add `VrmLoaderPlugin` on its own.
+#[derive(Default)]
+pub struct FaceTrackingAppPlugin {
+    pub config: AppConfig,
+}
+
+impl FaceTrackingAppPlugin {
+    pub fn new(config: AppConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Plugin for FaceTrackingAppPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<AssetPlugin>() {
+            register_user_vrm_source(app, self.config.user_vrm_dir.clone()).add_plugins(
+                DefaultPlugins.set(AssetPlugin {
+                    file_path: "assets".to_string(),
+                    ..default()
+                }),
+            );
+        } else if app
+            .world()
+            .resource::<AssetServer>()
+            .get_source(AssetSourceId::Name(USER_VRM_SOURCE.into()))
+            .is_err()
+        {
+            // Models picked in the file dialog load through this source
+            panic!(
+                "FaceTrackingAppPlugin needs the `{USER_VRM_SOURCE}://` asset source: \
+                 call register_user_vrm_source before adding AssetPlugin"
+            );
+        }
+        if !app.is_plugin_added::<VrmLoaderPlugin>() {
+            app.add_plugins(VrmLoaderPlugin);
+        }
+
+        let expression_adapter = ExpressionAdapter {
+            adapter: ArkitToVrmAdapter::with_config(self.config.expressions.clone()),
+            calibrate_mouth_pending: false,
+            calibrate_neutral_pending: false,
+        };
+
+        app.insert_resource(Config {
+            inner: self.config.clone(),
+        })
+        .insert_resource(self.config.presets.clone())
+        .init_resource::<VrmModelPath>()
+        .insert_resource(expression_adapter)
+        .init_resource::<CurrentExpressions>()
+        .init_resource::<UnmappedExpressions>()
+        .init_resource::<TrackingPaused>()
+        .init_resource::<TrackerCapabilities>()
+        .init_resource::<AppliedExpressionWeights>()
+        .init_resource::<CurrentShoulderPosition>()
+        .init_resource::<TrackingQuality>()
+        .init_resource::<FrameHistory>()
+        .init_resource::<PipelineOutput>()
+        .init_resource::<ModelScale>()
+        .init_resource::<ExpressionEnableMask>()
+        .init_resource::<TrackerDiagnostics>()
+        .add_message::<ExpressionSnapshot>()
+        .add_message::<TrackerFrameReceived>()
+        .add_systems(
+            Startup,
+            (
+                setup_tracker,
+                setup_scene,
+                setup_file_dialog,
+                setup_pipeline_output,
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                update_tracking_paused,
+                receive_tracker_frames.after(update_tracking_paused),
+                record_tracking_quality_inputs.after(receive_tracker_frames),
+                observe_tracker_capabilities.after(receive_tracker_frames),
+                observe_tracker_camera.after(receive_tracker_frames),
+                update_tracking_quality.after(record_tracking_quality_inputs),
+                (calibrate_expressions, dump_tracker_frames)
+                    .chain()
+                    .after(receive_tracker_frames)
+                    .run_if(tracking_active),
+                handle_camera_input,
+                handle_preset_input,
+                handle_expression_toggle_input,
+                check_vrm_load_status,
+                auto_frame_on_load,
+                update_model_scale,
+                handle_file_dialog_input,
+                receive_file_dialog_result,
+                load_vrm_from_path,
+                build_expression_maps,
+                (scrub_frame_history, record_frame_history)
+                    .chain()
+                    .after(dump_tracker_frames),
+                (apply_expressions, apply_body_position)
+                    .after(scrub_frame_history)
+                    .after(update_model_scale)
+                    .run_if(tracking_active),
+                write_pipeline_output
+                    .after(apply_expressions)
+                    .run_if(tracking_active),
+            ),
+        );
+
+        #[cfg(feature = "http")]
+        app.add_systems(Startup, fetch_vrm_from_url.after(setup_file_dialog));
+    }
+}
+
+fn setup_tracker(mut commands: Commands, config: Res<Config>) {
+    let tracker_config = &config.inner.tracker;
+    // Use PYTHON_BIN environment variable if set, then the config, otherwise "python3"
+    let python_bin = std::env::var("PYTHON_BIN").unwrap_or_else(|_| {
+        tracker_config.python_path.as_ref().map_or_else(
+            || "python3".to_string(),
+            |path| path.to_string_lossy().into_owned(),
+        )
+    });
+
+    // Resolve the script against our own working directory, since the tracker
+    // may be started in a different one
+    let script_path = tracker_config
+        .script_path
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("tools/mediapipe_tracker.py"));
+    let script_path = std::path::absolute(&script_path).unwrap_or(script_path);
+
+    let mut command = TrackerCommand::new(&python_bin, &script_path.to_string_lossy())
+        .args(&tracker_config.script_args(config.inner.camera_device_id));
+    if let Some(working_dir) = &tracker_config.working_dir {
+        command = command.current_dir(working_dir);
+    }
+    for (key, value) in tracker_config.env.iter() {
+        command = command.env(key, value);
+    }
+    let (mut child, rx) = spawn_tracker_with(&command);
+
+    commands.insert_resource(TrackerReceiver { rx });
+    commands.insert_resource(TrackerControlChannel {
+        stdin: child.stdin.take(),
+        camera_device_id: config.inner.camera_device_id,
+    });
+    commands.insert_resource(TrackerProcess { child });
+
+    println!("Tracker process started with Python: {python_bin}");
+    println!("Using camera device ID: {}", config.inner.camera_device_id);
+}
+
+/// Whether tracking should be paused given the config option and window focus.
+///
+/// `window_focused` is `None` when there is no primary window, in which case
+/// tracking keeps running.
+fn is_tracking_paused(pause_when_unfocused: bool, window_focused: Option<bool>) -> bool {
+    pause_when_unfocused && window_focused == Some(false)
+}
+
+/// System that pauses tracking while the primary window is unfocused, if enabled.
+fn update_tracking_paused(
+    config: Res<Config>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut tracking_paused: ResMut<TrackingPaused>,
+) {
+    let window_focused = window_query.single().ok().map(|window| window.focused);
+    let paused = is_tracking_paused(config.inner.pause_when_unfocused, window_focused);
+
+    if paused != tracking_paused.paused {
+        if paused {
+            println!("Window unfocused: tracking paused");
+        } else {
+            println!("Window focused: tracking resumed");
+        }
+        tracking_paused.paused = paused;
+    }
+}
+
+/// Number keys used to select a camera device index.
+const CAMERA_KEYS: [KeyCode; 10] = [
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// System that asks the tracker to switch to camera device N when number key N is pressed.
+///
+/// The switch is only requested here; [`observe_tracker_camera`] updates the
+/// current device once the tracker's frames report it.
+fn handle_camera_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut control: ResMut<TrackerControlChannel>,
+) {
+    let Some(camera_device_id) = CAMERA_KEYS
+        .iter()
+        .position(|key| keyboard_input.just_pressed(*key))
+        .map(|index| index as u32)
+    else {
+        return;
+    };
+    if camera_device_id == control.camera_device_id {
+        return;
+    }
+    let Some(stdin) = control.stdin.as_mut() else {
+        return;
+    };
+
+    let command = TrackerControl::SetCamera {
+        camera: CameraSelector::Index(camera_device_id),
+    };
+    match send_control(stdin, &command) {
+        Ok(()) => println!("Requested camera device ID: {camera_device_id}"),
+        Err(e) => eprintln!("Warning: Failed to send camera switch to tracker: {e}"),
+    }
+}
+
+/// System that tracks the camera the tracker reports capturing from.
+///
+/// A failed switch leaves the reported camera unchanged, so the current
+/// device never reflects a camera that could not be opened.
+fn observe_tracker_camera(
+    mut frames: MessageReader<TrackerFrameReceived>,
+    mut control: ResMut<TrackerControlChannel>,
+) {
+    for TrackerFrameReceived(frame) in frames.read() {
+        let Some(CameraSelector::Index(camera_device_id)) = frame.camera else {
+            continue;
+        };
+        if camera_device_id != control.camera_device_id {
+            println!("Tracker switched to camera device ID: {camera_device_id}");
+            control.camera_device_id = camera_device_id;
+        }
+    }
+}
+
+/// Function keys used to select a config preset by its position in
+/// [`ConfigPresets::list`].
+const PRESET_KEYS: [KeyCode; 12] = [
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+];
+
+/// System that switches config presets with the function keys.
+///
+/// `F<n>` applies the n-th preset. With Shift held, the current tuning
+/// options are captured into that slot instead (as `preset<n>` if it is
+/// empty) and the config file is saved.
+fn handle_preset_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut presets: ResMut<ConfigPresets>,
+    mut config: ResMut<Config>,
+    mut expression_adapter: ResMut<ExpressionAdapter>,
+) {
+    let Some(slot) = PRESET_KEYS
+        .iter()
+        .position(|key| keyboard_input.just_pressed(*key))
+    else {
+        return;
+    };
+    let name = presets.list().get(slot).map(|name| name.to_string());
+
+    if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        let name = name.unwrap_or_else(|| format!("preset{}", slot + 1));
+        presets.capture(&name, &config.inner);
+        config.inner.presets = presets.clone();
+        println!("Captured config preset: {name}");
+        if let Err(e) = config.inner.save() {
+            eprintln!("Warning: Failed to save config presets: {e}");
+        }
+        return;
+    }
+
+    let Some(name) = name else {
+        return;
+    };
+    if presets.apply(&name, &mut config.inner) {
+        expression_adapter.adapter.config = config.inner.expressions.clone();
+        println!("Applied config preset: {name}");
+    }
+}
+
+/// System that toggles expression groups: `G` for gaze, `B` for blink.
+fn handle_expression_toggle_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut enable_mask: ResMut<ExpressionEnableMask>,
+) {
+    for (key, group, label) in [
+        (KeyCode::KeyG, ExpressionGroup::Gaze, "Gaze"),
+        (KeyCode::KeyB, ExpressionGroup::Blink, "Blink"),
+    ] {
+        if keyboard_input.just_pressed(key) {
+            let enabled = enable_mask.toggle_group(group);
+            println!(
+                "{label} tracking {}",
+                if enabled { "enabled" } else { "disabled" }
+            );
+        }
+    }
+}
+
+/// System that recomputes the tracking quality score, reporting when it drops
+/// below or recovers above `TRACKING_QUALITY_WARN`.
+fn update_tracking_quality(time: Res<Time>, mut tracking_quality: ResMut<TrackingQuality>) {
+    let score = match tracking_quality.last_frame_secs {
+        Some(last_frame_secs) => compute_tracking_quality(
+            tracking_quality.pose_confidence,
+            tracking_quality.face_present,
+            time.elapsed_secs() - last_frame_secs,
+        ),
+        None => 0.0,
+    };
+
+    let was_good = tracking_quality.score >= TRACKING_QUALITY_WARN;
+    let is_good = score >= TRACKING_QUALITY_WARN;
+    if was_good && !is_good {
+        println!("Tracking quality dropped to {score:.2}");
+    } else if !was_good && is_good {
+        println!("Tracking quality recovered to {score:.2}");
+    }
+
+    tracking_quality.score = score;
+}
+
+/// System that records the tracking state about to be applied into
+/// [`FrameHistory`], unless scrubbing.
+fn record_frame_history(
+    time: Res<Time>,
+    tracking_paused: Res<TrackingPaused>,
+    current_expressions: Res<CurrentExpressions>,
+    shoulder_pos: Res<CurrentShoulderPosition>,
+    mut history: ResMut<FrameHistory>,
+) {
+    if tracking_paused.paused || history.scrub_index.is_some() {
+        return;
+    }
+    history.record(FrameSnapshot {
+        time_secs: time.elapsed_secs(),
+        expressions: current_expressions.expressions.clone(),
+        custom: current_expressions.custom.clone(),
+        body_midpoint: shoulder_pos.midpoint,
+    });
+}
+
+/// System that scrubs through [`FrameHistory`].
+///
+/// `H` toggles scrubbing, starting at the newest snapshot; the arrow keys
+/// step backwards and forwards. The selected snapshot replaces live tracking
+/// until scrubbing is toggled off.
+fn scrub_frame_history(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut history: ResMut<FrameHistory>,
+    mut current_expressions: ResMut<CurrentExpressions>,
+    mut shoulder_pos: ResMut<CurrentShoulderPosition>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyH) {
+        history.scrub_index = match history.scrub_index {
+            Some(_) => {
+                println!("Frame history: resumed live tracking");
+                None
+            }
+            None => history.snapshots.len().checked_sub(1),
+        };
+    }
+    let Some(mut index) = history.scrub_index else {
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        index = index.saturating_sub(1);
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        index = (index + 1).min(history.snapshots.len() - 1);
+    }
+    if keyboard_input.any_just_pressed([KeyCode::KeyH, KeyCode::ArrowLeft, KeyCode::ArrowRight]) {
+        let newest = history.snapshots.back().map_or(0.0, |s| s.time_secs);
+        println!(
+            "Frame history: {}/{} ({:.2}s ago)",
+            index + 1,
+            history.snapshots.len(),
+            newest - history.snapshots[index].time_secs
+        );
+    }
+    history.scrub_index = Some(index);
+    history.restore(index, &mut current_expressions, &mut shoulder_pos);
+}
+
+/// System that opens the configured pipeline output sink.
+fn setup_pipeline_output(config: Res<Config>, mut output: ResMut<PipelineOutput>) {
+    output.sink = match &config.inner.output {
+        PipelineOutputConfig::None => None,
+        PipelineOutputConfig::Tcp { address } => match TcpSink::connect(address) {
+            Ok(sink) => {
+                println!("Streaming pipeline output to {address}");
+                Some(Box::new(sink))
+            }
+            Err(e) => {
+                eprintln!("Warning: Failed to connect pipeline output to {address}: {e}");
+                None
+            }
+        },
+    };
+}
+
+/// System that writes one line per tracker frame to the pipeline output sink.
+///
+/// Weights come from the [`ExpressionSnapshot`], so the output matches what
+/// the model shows. Frames are told apart by their tracker timestamp, so app
+/// frames between tracker frames write nothing. Nothing is written while
+/// scrubbing [`FrameHistory`], and the sink is dropped after a write error,
+/// e.g. when the listening tool disconnects.
+fn write_pipeline_output(
+    mut snapshots: MessageReader<ExpressionSnapshot>,
+    current_expressions: Res<CurrentExpressions>,
+    shoulder_pos: Res<CurrentShoulderPosition>,
+    history: Res<FrameHistory>,
+    mut output: ResMut<PipelineOutput>,
+    mut last_ts: Local<f64>,
+) {
+    let Some(snapshot) = snapshots.read().last() else {
+        return;
+    };
+    if history.scrub_index.is_some() || current_expressions.ts == *last_ts {
+        return;
+    }
+    *last_ts = current_expressions.ts;
+    let Some(sink) = output.sink.as_mut() else {
+        return;
+    };
+
+    let frame = PipelineFrame::new(
+        current_expressions.ts,
+        &snapshot.weights,
+        shoulder_pos.midpoint.map(|midpoint| midpoint.to_array()),
+    );
+    let result = match frame.to_json_line() {
+        Ok(line) => sink.write_line(&line),
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = result {
+        eprintln!("Warning: Pipeline output failed, disabling it: {e}");
+        output.sink = None;
+    }
+}
+
+/// Run condition for systems that apply tracking to the model.
+fn tracking_active(tracking_paused: Res<TrackingPaused>) -> bool {
+    !tracking_paused.paused
+}
+
+/// System that drains the tracker channel, counting dropped frames.
+///
+/// Frames are forwarded as [`TrackerFrameReceived`] messages even while
+/// tracking is paused, so [`TrackingQuality`] keeps seeing them; the systems
+/// that convert and apply tracking do not run while paused.
+fn receive_tracker_frames(
+    rx: Res<TrackerReceiver>,
+    mut diagnostics: ResMut<TrackerDiagnostics>,
+    mut frames: MessageWriter<TrackerFrameReceived>,
+) {
+    while let Ok(frame) = rx.rx.try_recv() {
+        let gap = diagnostics.frame_gaps.observe(&frame);
+        if gap > 0 {
+            eprintln!(
+                "Warning: Dropped {gap} tracker frame(s) ({:.1}% overall)",
+                diagnostics.frame_gaps.drop_rate() * 100.0
+            );
+        }
+        frames.write(TrackerFrameReceived(frame));
+    }
+}
+
+/// System that records each frame's pose confidence and face presence for
+/// [`TrackingQuality`].
+fn record_tracking_quality_inputs(
+    time: Res<Time>,
+    mut frames: MessageReader<TrackerFrameReceived>,
+    mut tracking_quality: ResMut<TrackingQuality>,
+) {
+    for TrackerFrameReceived(frame) in frames.read() {
+        let visibilities: Vec<f32> = KEY_POSE_LANDMARKS
+            .iter()
+            .filter_map(|&idx| frame.pose_landmarks.get(idx).map(|lm| lm.visibility))
+            .collect();
+        tracking_quality.pose_confidence = if visibilities.is_empty() {
+            0.0
+        } else {
+            visibilities.iter().sum::<f32>() / visibilities.len() as f32
+        };
+        tracking_quality.face_present = !frame.blendshapes.is_empty();
+        tracking_quality.last_frame_secs = Some(time.elapsed_secs());
+    }
+}
+
+/// System that records which blendshapes the tracker reports.
+fn observe_tracker_capabilities(
+    mut frames: MessageReader<TrackerFrameReceived>,
+    mut capabilities: ResMut<TrackerCapabilities>,
+) {
+    for TrackerFrameReceived(frame) in frames.read() {
+        if capabilities.blendshapes.observe(frame) {
+            println!(
+                "Tracker reports {} blendshapes",
+                capabilities.blendshapes.keys().len()
+            );
+        }
+    }
+}
+
+/// System that calibrates the expression adapter against the next face frame
+/// while the user holds a neutral face (`N` for every blendshape, `C` for the
+/// resting mouth).
+///
+/// Key presses stay pending on [`ExpressionAdapter`] until a frame with
+/// blendshapes arrives, since the tracker runs slower than the app.
+fn calibrate_expressions(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut frames: MessageReader<TrackerFrameReceived>,
+    mut expression_adapter: ResMut<ExpressionAdapter>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyC) {
+        expression_adapter.calibrate_mouth_pending = true;
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyN) {
+        expression_adapter.calibrate_neutral_pending = true;
+    }
+
+    for TrackerFrameReceived(frame) in frames.read() {
+        if expression_adapter.calibrate_neutral_pending && !frame.blendshapes.is_empty() {
+            expression_adapter
+                .adapter
+                .neutral
+                .capture_neutral(&frame.blendshapes);
+            println!(
+                "Calibrated neutral face over {} blendshapes",
+                frame.blendshapes.len()
+            );
+            expression_adapter.calibrate_neutral_pending = false;
+        }
+
+        if expression_adapter.calibrate_mouth_pending && !frame.blendshapes.is_empty() {
+            expression_adapter
+                .adapter
+                .calibrate_mouth_rest(&frame.blendshapes);
+            println!(
+                "Calibrated mouth rest: jawOpen={:.2}",
+                expression_adapter.adapter.mouth_rest()
+            );
+            expression_adapter.calibrate_mouth_pending = false;
+        }
+    }
+}
+
+/// System that converts tracker frames into the current expressions and body
+/// position, printing what each frame contained.
+fn dump_tracker_frames(
+    mut frames: MessageReader<TrackerFrameReceived>,
+    expression_adapter: Res<ExpressionAdapter>,
+    mut current_expressions: ResMut<CurrentExpressions>,
+    mut shoulder_pos: ResMut<CurrentShoulderPosition>,
+) {
+    for TrackerFrameReceived(frame) in frames.read() {
+        // Use the expression adapter to convert ARKit blendshapes to VRM expressions
+        let vrm_expressions = expression_adapter
+            .adapter
+            .to_vrm_expressions(&frame.blendshapes);
+
+        // Store expressions for the apply_expressions system to use
+        current_expressions.expressions = vrm_expressions.clone();
+        current_expressions.custom = expression_adapter
+            .adapter
+            .custom_expressions(&frame.blendshapes);
+        current_expressions.ts = frame.ts;
+
+        // Print the converted expressions
+        if !vrm_expressions.is_empty() {
+            let expr_summary: Vec<String> = vrm_expressions
+                .iter()
+                .map(|e| format!("{}={:.2}", e.preset.as_str(), e.weight))
+                .collect();
+
+            println!(
+                "ts={:.3} expressions=[{}]",
+                frame.ts,
+                expr_summary.join(", ")
+            );
+        }
+
+        // Print pose landmark information if available
+        if !frame.pose_landmarks.is_empty() {
+            let pose_summary: Vec<String> = KEY_POSE_LANDMARKS
+                .iter()
+                .zip(KEY_POSE_LANDMARK_NAMES.iter())
+                .filter_map(|(&idx, &name)| {
+                    frame.pose_landmarks.get(idx).map(|lm| {
+                        format!(
+                            "{}=({:.2},{:.2},{:.2},v={:.2})",
+                            name, lm.x, lm.y, lm.z, lm.visibility
+                        )
+                    })
+                })
+                .collect();
+
+            if !pose_summary.is_empty() {
+                println!("ts={:.3} pose=[{}]", frame.ts, pose_summary.join(", "));
+            }
+        }
+
+        // Print pose world landmarks if available
+        if !frame.pose_world_landmarks.is_empty() {
+            let world_summary: Vec<String> = KEY_POSE_LANDMARKS
+                .iter()
+                .zip(KEY_POSE_LANDMARK_NAMES.iter())
+                .filter_map(|(&idx, &name)| {
+                    frame.pose_world_landmarks.get(idx).map(|lm| {
+                        format!(
+                            "{}=({:.3},{:.3},{:.3}m,v={:.2})",
+                            name, lm.x, lm.y, lm.z, lm.visibility
+                        )
+                    })
+                })
+                .collect();
+
+            if !world_summary.is_empty() {
+                println!(
+                    "ts={:.3} pose_world=[{}]",
+                    frame.ts,
+                    world_summary.join(", ")
+                );
+            }
+        }
+
+        // Update body position from shoulder world landmarks.
+        // Shoulder indices: 11 = left shoulder, 12 = right shoulder.
+        if frame.pose_world_landmarks.len() > RIGHT_SHOULDER_IDX {
+            let left = &frame.pose_world_landmarks[LEFT_SHOULDER_IDX];
+            let right = &frame.pose_world_landmarks[RIGHT_SHOULDER_IDX];
+
+            if left.visibility >= SHOULDER_VISIBILITY_THRESHOLD
+                && right.visibility >= SHOULDER_VISIBILITY_THRESHOLD
+            {
+                shoulder_pos.midpoint = Some(Vec3::new(
+                    (left.x + right.x) * 0.5,
+                    (left.y + right.y) * 0.5,
+                    (left.z + right.z) * 0.5,
+                ));
+            }
+        }
+    }
+}
+
+fn setup_scene(mut commands: Commands, config: Res<Config>) {
+    // Spawn camera
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 0.8, 1.5).looking_at(Vec3::new(0.0, 0.8, 0.0), Vec3::Y),
+    ));
+
+    // Spawn directional light
+    commands.spawn((
+        DirectionalLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(3.0, 3.0, 0.3).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    println!("Scene setup complete.");
+    println!(
+        "User VRM directory: {}",
+        config.inner.user_vrm_dir.display()
+    );
+    println!("Press 'O' to open a file dialog and select a VRM model to load.");
+    println!("Press 'C' with a neutral face to calibrate the resting mouth.");
+    println!("Press 'N' with a neutral face to calibrate every blendshape's resting bias.");
+}
+
+/// Fraction of the model's height kept in view, measured down from the top of the head.
+const AUTO_FRAME_VISIBLE_HEIGHT: f32 = 0.4;
+/// Extra room around the framed region.
+const AUTO_FRAME_MARGIN: f32 = 1.1;
+
+/// Compute the camera focus point and distance for a VTuber framing of a
+/// model with the given world-space bounds.
+///
+/// The upper `AUTO_FRAME_VISIBLE_HEIGHT` of the model fills the vertical
+/// field of view `fov_y` (radians), with `AUTO_FRAME_MARGIN` to spare. The
+/// distance is measured from the focus point, which sits on the model's
+/// vertical centre line.
+fn upper_body_framing(min: Vec3, max: Vec3, fov_y: f32) -> (Vec3, f32) {
+    let center = (min + max) * 0.5;
+    let half_visible = (max.y - min.y) * AUTO_FRAME_VISIBLE_HEIGHT * 0.5;
+    let focus = Vec3::new(center.x, max.y - half_visible, center.z);
+    let half_depth = (max.z - min.z) * 0.5;
+
+    let distance = half_visible / (fov_y * 0.5).tan() * AUTO_FRAME_MARGIN + half_depth;
+    (focus, distance)
+}
+
+/// System that frames the camera on a newly loaded model's upper body once
+/// its meshes have bounds.
+fn auto_frame_on_load(
+    mut commands: Commands,
+    vrm_query: Query<Entity, (With<CurrentVrmEntity>, Without<AutoFramed>)>,
+    children_query: Query<&Children>,
+    bounds_query: Query<(&Aabb, &GlobalTransform)>,
+    mut camera_query: Query<(&mut Transform, &Projection), With<Camera3d>>,
+) {
+    for vrm_entity in vrm_query.iter() {
+        let mut min = Vec3::splat(f32::MAX);
+        let mut max = Vec3::splat(f32::MIN);
+        for (aabb, transform) in bounds_query.iter_many(children_query.iter_descendants(vrm_entity))
+        {
+            let (center, half) = (Vec3::from(aabb.center), Vec3::from(aabb.half_extents));
+            for corner in [
+                Vec3::new(-1.0, -1.0, -1.0),
+                Vec3::new(-1.0, -1.0, 1.0),
+                Vec3::new(-1.0, 1.0, -1.0),
+                Vec3::new(-1.0, 1.0, 1.0),
+                Vec3::new(1.0, -1.0, -1.0),
+                Vec3::new(1.0, -1.0, 1.0),
+                Vec3::new(1.0, 1.0, -1.0),
+                Vec3::new(1.0, 1.0, 1.0),
+            ] {
+                let point = transform.transform_point(center + half * corner);
+                min = min.min(point);
+                max = max.max(point);
+            }
+        }
+        // Meshes have not been spawned or bounded yet
+        if min.y >= max.y {
+            continue;
+        }
+
+        for (mut camera_transform, projection) in camera_query.iter_mut() {
+            let fov_y = match projection {
+                Projection::Perspective(perspective) => perspective.fov,
+                _ => PerspectiveProjection::default().fov,
+            };
+            let (focus, distance) = upper_body_framing(min, max, fov_y);
+            // VRM 1.0 models face +Z
+            *camera_transform =
+                Transform::from_translation(focus + Vec3::Z * distance).looking_at(focus, Vec3::Y);
+        }
+        commands.entity(vrm_entity).insert(AutoFramed);
+    }
+}
+
+fn check_vrm_load_status(
+    mut events: MessageReader<AssetEvent<VrmAsset>>,
+    mut reported: Local<bool>,
+) {
+    for event in events.read() {
+        match event {
+            AssetEvent::Added { .. } => {
+                if !*reported {
+                    println!("✓ VRM model loaded successfully");
+                    *reported = true;
+                }
+            }
+            AssetEvent::LoadedWithDependencies { .. } => {
+                if !*reported {
+                    println!("✓ VRM model and dependencies loaded successfully");
+                    *reported = true;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn setup_file_dialog(mut commands: Commands) {
+    let (tx, rx) = crossbeam_channel::unbounded();
+    commands.insert_resource(FileDialogChannel {
+        tx: Arc::new(Mutex::new(tx)),
+        rx,
+    });
+}
+
+fn handle_file_dialog_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    file_dialog_channel: Res<FileDialogChannel>,
+    config: Res<Config>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyO) {
+        println!("Opening file dialog...");
+
+        let tx = file_dialog_channel.tx.clone();
+        let user_vrm_dir = config.inner.user_vrm_dir.clone();
+
+        // Spawn a thread to open the file dialog without blocking the main thread
+        std::thread::spawn(move || {
+            let file = rfd::FileDialog::new()
+                .add_filter("VRM Model", &["vrm"])
+                .set_title("Select VRM Model")
+                .set_directory(&user_vrm_dir)
+                .pick_file();
+
+            if let Some(path) = &file {
+                println!("Selected file: {}", path.display());
+            } else {
+                println!("File selection cancelled");
+            }
+
+            // Send the result through the channel
+            if let Ok(sender) = tx.lock() {
+                let _ = sender.send(file);
+            }
+        });
+    }
+}
+
+/// Download the VRM model given in the `VRM_URL` environment variable.
+///
+/// The download runs on a separate thread. The model is validated, saved to the
+/// user VRM directory without replacing other models (see
+/// [`download_destination`]) and then loaded the same way as a file picked in
+/// the file dialog.
+#[cfg(feature = "http")]
+fn fetch_vrm_from_url(file_dialog_channel: Res<FileDialogChannel>, config: Res<Config>) {
+    let Ok(url) = std::env::var("VRM_URL") else {
+        return;
+    };
+
+    println!("Downloading VRM model from: {url}");
+
+    let tx = file_dialog_channel.tx.clone();
+    let user_vrm_dir = config.inner.user_vrm_dir.clone();
+
+    std::thread::spawn(move || {
+        let result = vrm_loader::fetch_vrm_bytes(&url).and_then(|bytes| {
+            // Reject responses that are not VRM models before saving them
+            vrm_loader::parse_vrm_bytes(&bytes, Handle::default())?;
+            Ok(bytes)
+        });
+
+        let bytes = match result {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                eprintln!("Failed to download VRM model: {e}");
+                return;
+            }
+        };
+
+        let file_name = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| path.rsplit('/').next())
+            .filter(|name| name.ends_with(".vrm"))
+            .unwrap_or("downloaded.vrm");
+        let dest_path = download_destination(&user_vrm_dir, file_name, &bytes);
+
+        if dest_path.exists() {
+            println!(
+                "Downloaded VRM model already saved at: {}",
+                dest_path.display()
+            );
+        } else if let Err(e) = std::fs::write(&dest_path, &bytes) {
+            eprintln!("Failed to save downloaded VRM model: {e}");
+            return;
+        } else {
+            println!("Saved downloaded VRM model to: {}", dest_path.display());
+        }
+
+        // Hand the file over to the regular loading path
+        if let Ok(sender) = tx.lock() {
+            let _ = sender.send(Some(dest_path));
+        }
+    });
+}
+
+/// Pick where to save a downloaded model in `user_vrm_dir`.
+///
+/// An existing file is only reused when it holds the same bytes (e.g. the same
+/// `VRM_URL` on every start); otherwise a numbered name such as
+/// `avatar-1.vrm` is chosen, so other models are never overwritten.
+#[cfg(feature = "http")]
+fn download_destination(user_vrm_dir: &std::path::Path, file_name: &str, bytes: &[u8]) -> PathBuf {
+    let stem = file_name.strip_suffix(".vrm").unwrap_or(file_name);
+    let mut candidate = user_vrm_dir.join(file_name);
+    let mut suffix = 1;
+    while candidate.exists() && std::fs::read(&candidate).ok().as_deref() != Some(bytes) {
+        candidate = user_vrm_dir.join(format!("{stem}-{suffix}.vrm"));
+        suffix += 1;
+    }
+    candidate
+}
+
+fn receive_file_dialog_result(
+    file_dialog_channel: Res<FileDialogChannel>,
+    mut vrm_path: ResMut<VrmModelPath>,
+) {
+    while let Ok(result) = file_dialog_channel.rx.try_recv() {
+        if let Some(path) = result {
+            println!("Received selected file: {}", path.display());
+            vrm_path.path = Some(path);
+        }
+    }
+}
+
+fn load_vrm_from_path(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut vrm_path: ResMut<VrmModelPath>,
+    current_vrm_query: Query<Entity, With<CurrentVrmEntity>>,
+    config: Res<Config>,
+    mut unmapped: ResMut<UnmappedExpressions>,
+) {
+    if let Some(path) = vrm_path.path.take() {
+        // Remove the current VRM entity if it exists
+        for entity in current_vrm_query.iter() {
+            commands.entity(entity).despawn();
+        }
+
+        // Missing expressions are reported per model
+        unmapped.clear();
+
+        // Copy the file to the user VRM directory so Bevy can load it
+        let user_vrm_dir = &config.inner.user_vrm_dir;
+        if let Err(e) = std::fs::create_dir_all(user_vrm_dir) {
+            eprintln!("Failed to create user VRM directory: {e}");
+            return;
+        }
+
+        let file_name = path
+            .file_name()
+            .unwrap_or_else(|| std::ffi::OsStr::new("model.vrm"));
+        let dest_path = user_vrm_dir.join(file_name);
+
+        // Only copy if source and destination are different
+        if path != dest_path {
+            if let Err(e) = std::fs::copy(&path, &dest_path) {
+                eprintln!("Failed to copy VRM file to user directory: {e}");
+                return;
+            }
+            println!("Copied VRM file to: {}", dest_path.display());
+        }
+
+        // Load the VRM model via the userdata asset source
+        let asset_path = format!("userdata://{}", file_name.to_string_lossy());
+        println!("Loading VRM model from user data: {asset_path}");
+        let vrm_handle: Handle<VrmAsset> = asset_server.load(&asset_path);
+        commands.spawn((
+            VrmHandle(vrm_handle),
+            CurrentVrmEntity,
+            Transform::default(),
+        ));
+    }
+}
+
+/// System that builds VRM expression maps for entities with MorphWeights.
+///
+/// This system runs after a VRM scene is spawned and builds the mapping from
+/// expression names to morph target indices for each entity with MorphWeights.
+///
+/// In Bevy's glTF loader, MorphWeights is attached to parent node entities,
+/// while MeshMorphWeights is on the child mesh primitive entities. When we update
+/// MorphWeights on the parent, it automatically syncs to the children.
+#[allow(clippy::type_complexity)]
+fn build_expression_maps(
+    mut commands: Commands,
+    vrm_assets: Res<Assets<VrmAsset>>,
+    gltf_assets: Res<Assets<bevy::gltf::Gltf>>,
+    vrm_entities: Query<
+        (Entity, &VrmHandle, &Children),
+        (With<CurrentVrmEntity>, Without<VrmExpressionMap>),
+    >,
+    children_query: Query<&Children>,
+    morph_weights_query: Query<Entity, With<MorphWeights>>,
+    mut expression_adapter: ResMut<ExpressionAdapter>,
+) {
+    for (vrm_entity, vrm_handle, children) in vrm_entities.iter() {
+        let Some(vrm_asset) = vrm_assets.get(&vrm_handle.0) else {
+            continue;
+        };
+
+        let Some(_gltf) = gltf_assets.get(&vrm_asset.gltf) else {
+            continue;
+        };
+
+        // Collect all entities with MorphWeights in the scene
+        let mut morph_entities = Vec::new();
+        collect_morph_weight_entities(
+            children,
+            &children_query,
+            &morph_weights_query,
+            &mut morph_entities,
+        );
+
+        // Build expression maps
+        // We create a combined expression map with all morph target bindings
+        // and apply it to all entities with MorphWeights
+        let mut combined_expr_map = VrmExpressionMap {
+            expression_to_morphs: HashMap::new(),
+        };
+
+        for (expression_name, expression_data) in vrm_asset.expressions.iter() {
+            for morph_bind in expression_data.morph_target_binds.iter() {
+                // Binds past their mesh's morph targets were reported as load warnings
+                let in_range = vrm_asset
+                    .nodes
+                    .get(morph_bind.node)
+                    .is_none_or(|node| node.has_morph_target(morph_bind.index));
+                if !in_range {
+                    continue;
+                }
+
+                combined_expr_map
+                    .expression_to_morphs
+                    .entry(expression_name.clone())
+                    .or_default()
+                    .push((morph_bind.index, morph_bind.weight));
+            }
+        }
+
+        // Apply the expression map to all entities with MorphWeights
+        for &morph_entity in &morph_entities {
+            commands
+                .entity(morph_entity)
+                .insert(combined_expr_map.clone());
+        }
+
+        // Let the adapter route custom expressions (e.g. smirks) to this model
+        expression_adapter.adapter.model_expressions =
+            vrm_asset.expressions.keys().cloned().collect();
+
+        // Mark the VRM entity as processed
+        commands.entity(vrm_entity).insert(VrmExpressionMap {
+            expression_to_morphs: HashMap::new(),
+        });
+
+        info!(
+            "Built expression maps for VRM: {} ({} morph entities)",
+            vrm_asset.meta.name,
+            morph_entities.len()
+        );
+    }
+}
+
+/// Helper function to collect all entities with MorphWeights from the scene hierarchy
+fn collect_morph_weight_entities(
+    children: &Children,
+    children_query: &Query<&Children>,
+    morph_weights_query: &Query<Entity, With<MorphWeights>>,
+    morph_entities: &mut Vec<Entity>,
+) {
+    for child in children.iter() {
+        // Check if this child has MorphWeights
+        if morph_weights_query.get(child).is_ok() {
+            morph_entities.push(child);
+        }
+
+        // Recursively check children
+        if let Ok(grandchildren) = children_query.get(child) {
+            collect_morph_weight_entities(
+                grandchildren,
+                children_query,
+                morph_weights_query,
+                morph_entities,
+            );
+        }
+    }
+}
+
+/// Resources [`apply_expressions`] carries from one frame to the next.
+#[derive(SystemParam)]
+struct ExpressionApplyState<'w> {
+    applied: ResMut<'w, AppliedExpressionWeights>,
+    enable_mask: ResMut<'w, ExpressionEnableMask>,
+    unmapped: ResMut<'w, UnmappedExpressions>,
+}
+
+/// System that applies VRM expressions to mesh morph weights.
+///
+/// This system takes the current VRM expressions from face tracking and applies
+/// them to the mesh entities' MorphWeights components. Expressions the model has
+/// no bindings for are recorded in [`UnmappedExpressions`] and reported once.
+fn apply_expressions(
+    current_expressions: Res<CurrentExpressions>,
+    config: Res<Config>,
+    time: Res<Time>,
+    mut state: ExpressionApplyState,
+    mut mesh_query: Query<(&VrmExpressionMap, &mut MorphWeights)>,
+    mut snapshots: MessageWriter<ExpressionSnapshot>,
+) {
+    // Runs on frames without expressions too, so inverted expressions apply
+    // and rate-limited weights fade out
+    let expressions = match config.inner.expression_apply.max_weight_rate {
+        Some(max_rate) => state.applied.limit(
+            &current_expressions.expressions,
+            max_rate,
+            time.delta_secs(),
+        ),
+        None => current_expressions.expressions.clone(),
+    };
+    let expressions = state.enable_mask.apply(&expressions, time.delta_secs());
+    let custom =
+        applied_custom_weights(&current_expressions.custom, &config.inner.expression_apply);
+
+    for (expr_map, mut morph_weights) in mesh_query.iter_mut() {
+        // We need to know the total number of morph targets for this mesh
+        let num_morph_targets = morph_weights.weights().len();
+        let mut new_weights = compute_morph_weights(
+            expr_map,
+            &expressions,
+            num_morph_targets,
+            &config.inner.expression_apply,
+            &mut state.unmapped,
+        );
+        for &(name, weight) in &custom {
+            add_custom_expression(
+                expr_map,
+                name,
+                weight,
+                config.inner.expression_apply.morph_combine,
+                &mut new_weights,
+            );
+        }
+
+        // Update the morph weights
+        morph_weights.weights_mut().copy_from_slice(&new_weights);
+    }
+
+    snapshots.write(ExpressionSnapshot {
+        weights: applied_expression_weights(&expressions, &config.inner.expression_apply)
+            .into_iter()
+            .chain(custom)
+            .map(|(name, weight)| (name.to_string(), weight))
+            .collect(),
+    });
+}
+
+/// Add a custom expression's weight on top of computed morph weights.
+///
+/// Models without the expression are left untouched, since custom
+/// expressions are optional extras rather than spec presets.
+fn add_custom_expression(
+    expr_map: &VrmExpressionMap,
+    name: &str,
+    weight: f32,
+    morph_combine: MorphCombine,
+    morph_weights: &mut [f32],
+) {
+    let Some(morph_bindings) = expr_map.expression_to_morphs.get(name) else {
+        return;
+    };
+    for &(morph_idx, base_weight) in morph_bindings {
+        if let Some(morph_weight) = morph_weights.get_mut(morph_idx) {
+            *morph_weight = morph_combine
+                .combine(*morph_weight, weight * base_weight)
+                .clamp(0.0, 1.0);
+        }
+    }
+}
+
+/// Whether `name` is a VRM preset expression rather than a custom one.
+fn is_preset_name(name: &str) -> bool {
+    VrmExpressionPreset::ALL
+        .iter()
+        .any(|preset| preset.as_str() == name)
+}
+
+/// Weight of each VRM preset expression as applied to the model, keyed by name.
+///
+/// Presets marked as inverted in `apply_config` are applied as
+/// `1.0 - weight`, treating an absent expression as weight 0.0. Weights are
+/// scaled by the master expression intensity before inverting, so a master of
+/// 0.0 leaves every morph neutral. Inverted custom expressions are handled by
+/// [`applied_custom_weights`].
+fn applied_expression_weights<'a>(
+    expressions: &[VrmExpression],
+    apply_config: &'a ExpressionApplyConfig,
+) -> HashMap<&'a str, f32> {
+    // Build a map from expression name to weight, scaled by the master intensity
+    let master = apply_config.master_expression_intensity.max(0.0);
+    let mut expression_weights: HashMap<&str, f32> = HashMap::new();
+    for expr in expressions.iter() {
+        expression_weights.insert(expr.preset.as_str(), expr.weight * master);
+    }
+
+    // Invert backwards-authored expressions, including ones absent this frame
+    for (name, expression_override) in apply_config.overrides.iter() {
+        if expression_override.invert && is_preset_name(name) {
+            let weight = expression_weights.entry(name.as_str()).or_insert(0.0);
+            *weight = 1.0 - *weight;
+        }
+    }
+
+    expression_weights
+}
+
+/// Weight of each custom expression as applied to the model.
+///
+/// Like [`applied_expression_weights`], weights are scaled by the master
+/// intensity and then inverted for overrides naming a custom expression,
+/// treating an absent one as weight 0.0.
+fn applied_custom_weights<'a>(
+    custom: &[(&'static str, f32)],
+    apply_config: &'a ExpressionApplyConfig,
+) -> Vec<(&'a str, f32)> {
+    let master = apply_config.master_expression_intensity.max(0.0);
+    let mut weights: Vec<(&str, f32)> = custom
+        .iter()
+        .map(|&(name, weight)| (name, weight * master))
+        .collect();
+
+    for (name, expression_override) in apply_config.overrides.iter() {
+        if !expression_override.invert || is_preset_name(name) {
+            continue;
+        }
+        match weights
+            .iter_mut()
+            .find(|(custom_name, _)| custom_name == name)
+        {
+            Some((_, weight)) => *weight = 1.0 - *weight,
+            None => weights.push((name.as_str(), 1.0)),
+        }
+    }
+
+    weights
+}
+
+/// Calculate the morph weights for a mesh from the current VRM expressions.
+///
+/// Expressions with no entry in the expression map are recorded in `unmapped`,
+/// printing a notice the first time each name is seen. Weights are those of
+/// [`applied_expression_weights`].
+fn compute_morph_weights(
+    expr_map: &VrmExpressionMap,
+    expressions: &[VrmExpression],
+    num_morph_targets: usize,
+    apply_config: &ExpressionApplyConfig,
+    unmapped: &mut UnmappedExpressions,
+) -> Vec<f32> {
+    let expression_weights = applied_expression_weights(expressions, apply_config);
+
+    let mut new_weights = vec![0.0; num_morph_targets];
+
+    // Apply each expression
+    for (expr_name, expr_weight) in expression_weights.iter() {
+        let Some(morph_bindings) = expr_map.expression_to_morphs.get(*expr_name) else {
+            if unmapped.record(expr_name) {
+                println!("Loaded model has no '{expr_name}' expression; its weight is ignored");
+            }
+            continue;
+        };
+
+        for &(morph_idx, base_weight) in morph_bindings {
+            if morph_idx < num_morph_targets {
+                new_weights[morph_idx] = apply_config
+                    .morph_combine
+                    .combine(new_weights[morph_idx], expr_weight * base_weight);
+            }
+        }
+    }
+
+    // Clamp weights to [0, 1]
+    for weight in new_weights.iter_mut() {
+        *weight = weight.clamp(0.0, 1.0);
+    }
+
+    new_weights
+}
+
+/// System that translates the VRM root entity based on shoulder world landmarks.
+///
+/// The translation is in meters, multiplied by [`ModelScale`] so models
+/// exported at other scales move proportionally.
+///
+/// The midpoint of the two shoulder world landmarks (MediaPipe indices 11 & 12)
+/// is used to compute where the model's root (feet) should be placed in Bevy
+/// world space, so that the model tracks the subject's real-world torso position.
+///
+/// Each axis is multiplied by its sign constant (`BODY_*_SIGN`) and scale constant
+/// (`BODY_*_SCALE`) so movement can be flipped or amplified by editing those values.
+///
+/// Coordinate mapping (with default signs/scales of ±1.0 / 1.0):
+/// - MediaPipe world X (person's right) → Bevy world X
+/// - MediaPipe world Y (up, origin at hip centre) → Bevy world Y with `SHOULDER_Y_OFFSET`
+/// - MediaPipe world Z (toward camera) → Bevy world Z
+fn apply_body_position(
+    shoulder_pos: Res<CurrentShoulderPosition>,
+    model_scale: Res<ModelScale>,
+    mut vrm_query: Query<&mut Transform, With<CurrentVrmEntity>>,
+) {
+    let Some(midpoint) = shoulder_pos.midpoint else {
+        return;
+    };
+
+    for mut transform in vrm_query.iter_mut() {
+        transform.translation = body_translation(midpoint, model_scale.scale);
+    }
+}
+
+/// Map a shoulder midpoint in meters to the VRM root translation in the
+/// model's own units.
+fn body_translation(midpoint: Vec3, model_scale: f32) -> Vec3 {
+    Vec3::new(
+        midpoint.x * BODY_X_SIGN * BODY_X_SCALE,
+        (midpoint.y + SHOULDER_Y_OFFSET) * BODY_Y_SIGN * BODY_Y_SCALE,
+        midpoint.z * BODY_Z_SIGN * BODY_Z_SCALE,
+    ) * model_scale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::intern::Interned;
+    use bevy::ecs::schedule::ScheduleLabel;
+    use expression_adapter::EYE_WIDE_EXPRESSION;
+    use std::any::TypeId;
+
+    #[test]
+    fn test_unmapped_expression_is_recorded_once() {
+        let expr_map = VrmExpressionMap {
+            expression_to_morphs: HashMap::from([("happy".to_string(), vec![(0, 1.0)])]),
+        };
+        let expressions = vec![
+            VrmExpression::new(VrmExpressionPreset::Happy, 0.5),
+            VrmExpression::new(VrmExpressionPreset::Surprised, 0.8),
+        ];
+        let mut unmapped = UnmappedExpressions::default();
+
+        let apply_config = ExpressionApplyConfig::default();
+
+        let weights =
+            compute_morph_weights(&expr_map, &expressions, 2, &apply_config, &mut unmapped);
+        assert_eq!(weights, vec![0.5, 0.0]);
+        assert_eq!(unmapped.names().collect::<Vec<_>>(), ["surprised"]);
+
+        // A second frame must not duplicate the entry
+        compute_morph_weights(&expr_map, &expressions, 2, &apply_config, &mut unmapped);
+        assert_eq!(unmapped.names().collect::<Vec<_>>(), ["surprised"]);
+        assert!(!unmapped.record("surprised"));
+    }
+
+    #[test]
+    fn test_tracking_paused_gating() {
+        // Option disabled: never paused
+        assert!(!is_tracking_paused(false, Some(false)));
+        assert!(!is_tracking_paused(false, Some(true)));
+
+        // Option enabled: paused only while unfocused
+        assert!(is_tracking_paused(true, Some(false)));
+        assert!(!is_tracking_paused(true, Some(true)));
+
+        // No window to lose focus
+        assert!(!is_tracking_paused(true, None));
+    }
+
+    #[test]
+    fn test_inverted_expression_applies_complement() {
+        let expr_map = VrmExpressionMap {
+            expression_to_morphs: HashMap::from([
+                ("happy".to_string(), vec![(0, 1.0)]),
+                ("sad".to_string(), vec![(1, 1.0)]),
+            ]),
+        };
+        let mut apply_config = ExpressionApplyConfig::default();
+        apply_config.overrides.insert(
+            "happy".to_string(),
+            config::ExpressionOverride { invert: true },
+        );
+        let mut unmapped = UnmappedExpressions::default();
+
+        let expressions = vec![
+            VrmExpression::new(VrmExpressionPreset::Happy, 0.3),
+            VrmExpression::new(VrmExpressionPreset::Sad, 0.3),
+        ];
+        let weights =
+            compute_morph_weights(&expr_map, &expressions, 2, &apply_config, &mut unmapped);
+        assert!((weights[0] - 0.7).abs() < 1e-6);
+        assert!((weights[1] - 0.3).abs() < 1e-6);
+
+        // An inverted expression absent from the frame is fully applied
+        let expressions = vec![VrmExpression::new(VrmExpressionPreset::Sad, 0.3)];
+        let weights =
+            compute_morph_weights(&expr_map, &expressions, 2, &apply_config, &mut unmapped);
+        assert_eq!(weights[0], 1.0);
+    }
+
+    #[test]
+    fn test_master_expression_intensity_scales_weights() {
+        let expr_map = VrmExpressionMap {
+            expression_to_morphs: HashMap::from([
+                ("happy".to_string(), vec![(0, 1.0)]),
+                ("aa".to_string(), vec![(1, 0.5)]),
+            ]),
+        };
+        let expressions = vec![
+            VrmExpression::new(VrmExpressionPreset::Happy, 0.8),
+            VrmExpression::new(VrmExpressionPreset::Aa, 0.6),
+        ];
+        let mut unmapped = UnmappedExpressions::default();
+
+        let full = ExpressionApplyConfig::default();
+        let half = ExpressionApplyConfig {
+            master_expression_intensity: 0.5,
+            ..Default::default()
+        };
+        let full_weights = compute_morph_weights(&expr_map, &expressions, 2, &full, &mut unmapped);
+        let half_weights = compute_morph_weights(&expr_map, &expressions, 2, &half, &mut unmapped);
+        for (full, half) in full_weights.iter().zip(&half_weights) {
+            assert!((half - full * 0.5).abs() < 1e-6);
+        }
+
+        // Exaggerated weights still clamp per morph target
+        let double = ExpressionApplyConfig {
+            master_expression_intensity: 2.0,
+            ..Default::default()
+        };
+        let weights = compute_morph_weights(&expr_map, &expressions, 2, &double, &mut unmapped);
+        assert_eq!(weights[0], 1.0);
+        assert!((weights[1] - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_custom_expression_applies_when_defined() {
+        let mut weights = vec![0.5, 0.0];
+        let without = VrmExpressionMap {
+            expression_to_morphs: HashMap::new(),
+        };
+        add_custom_expression(
+            &without,
+            EYE_WIDE_EXPRESSION,
+            0.8,
+            MorphCombine::Add,
+            &mut weights,
+        );
+        assert_eq!(weights, vec![0.5, 0.0]);
+
+        let with = VrmExpressionMap {
+            expression_to_morphs: HashMap::from([(
+                EYE_WIDE_EXPRESSION.to_string(),
+                vec![(0, 1.0), (1, 0.5)],
+            )]),
+        };
+        add_custom_expression(
+            &with,
+            EYE_WIDE_EXPRESSION,
+            0.8,
+            MorphCombine::Add,
+            &mut weights,
+        );
+        assert_eq!(weights[0], 1.0); // Clamped
+        assert!((weights[1] - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_frame_history_bounded_and_restores() {
+        let mut history = FrameHistory::new(3);
+        for i in 0..5 {
+            history.record(FrameSnapshot {
+                time_secs: i as f32,
+                expressions: vec![VrmExpression::new(
+                    VrmExpressionPreset::Happy,
+                    i as f32 * 0.1,
+                )],
+                custom: Vec::new(),
+                body_midpoint: Some(Vec3::splat(i as f32)),
+            });
+        }
+        assert_eq!(history.snapshots.len(), 3);
+        assert_eq!(history.snapshots[0].time_secs, 2.0);
+
+        let mut current_expressions = CurrentExpressions::default();
+        let mut shoulder_pos = CurrentShoulderPosition::default();
+        assert!(history.restore(1, &mut current_expressions, &mut shoulder_pos));
+        assert!((current_expressions.expressions[0].weight - 0.3).abs() < 1e-6);
+        assert_eq!(shoulder_pos.midpoint, Some(Vec3::splat(3.0)));
+
+        assert!(!history.restore(3, &mut current_expressions, &mut shoulder_pos));
+    }
+
+    #[test]
+    fn test_upper_body_framing() {
+        // tan(fov / 2) = 0.5
+        let fov_y = 2.0 * 0.5_f32.atan();
+        let (focus, distance) =
+            upper_body_framing(Vec3::new(-0.3, 0.0, -0.1), Vec3::new(0.3, 1.6, 0.1), fov_y);
+
+        // The top 0.64 of the model is centred in view
+        assert!((focus - Vec3::new(0.0, 1.28, 0.0)).length() < 1e-5);
+        assert!((distance - (0.32 / 0.5 * AUTO_FRAME_MARGIN + 0.1)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_shared_morph_combine_policies() {
+        let expr_map = VrmExpressionMap {
+            expression_to_morphs: HashMap::from([
+                ("happy".to_string(), vec![(3, 1.0)]),
+                ("relaxed".to_string(), vec![(3, 0.5), (1, 1.0)]),
+            ]),
+        };
+        let expressions = vec![
+            VrmExpression::new(VrmExpressionPreset::Happy, 0.6),
+            VrmExpression::new(VrmExpressionPreset::Relaxed, 0.8),
+        ];
+        let mut apply_config = ExpressionApplyConfig::default();
+        let mut unmapped = UnmappedExpressions::default();
+
+        // Both contributions count: 0.6 + 0.4, not whichever was written last
+        let weights =
+            compute_morph_weights(&expr_map, &expressions, 4, &apply_config, &mut unmapped);
+        assert!((weights[3] - 1.0).abs() < 1e-6);
+        assert!((weights[1] - 0.8).abs() < 1e-6);
+
+        apply_config.morph_combine = MorphCombine::Max;
+        let weights =
+            compute_morph_weights(&expr_map, &expressions, 4, &apply_config, &mut unmapped);
+        assert!((weights[3] - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_body_translation_follows_model_scale() {
+        // A model exported at 0.01 scale has its hips 0.009 units up
+        assert_eq!(estimate_model_scale(Some(0.9)), 1.0);
+        assert_eq!(estimate_model_scale(None), 1.0);
+        let small = estimate_model_scale(Some(0.009));
+        assert!((small - 0.01).abs() < 1e-6);
+
+        let midpoint = Vec3::new(0.2, 0.5, -0.1);
+        let meters = body_translation(midpoint, 1.0);
+        let scaled = body_translation(midpoint, small);
+        assert!((scaled - meters * 0.01).length() < 1e-6);
+    }
+
+    #[test]
+    fn test_expression_snapshot_carries_applied_weights() {
+        let mut config = AppConfig::default();
+        config.expression_apply.master_expression_intensity = 0.5;
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_message::<ExpressionSnapshot>()
+            .insert_resource(Config { inner: config })
+            .insert_resource(CurrentExpressions {
+                expressions: vec![VrmExpression::new(VrmExpressionPreset::Happy, 0.6)],
+                custom: vec![(EYE_WIDE_EXPRESSION, 0.3)],
+                ts: 0.0,
+            })
+            .init_resource::<AppliedExpressionWeights>()
+            .init_resource::<ExpressionEnableMask>()
+            .init_resource::<UnmappedExpressions>()
+            .add_systems(Update, apply_expressions);
+        app.update();
+        app.world_mut()
+            .resource_mut::<CurrentExpressions>()
+            .expressions
+            .clear();
+        app.update();
+
+        // Sent every frame, including ones without tracked expressions
+        let messages = app.world().resource::<Messages<ExpressionSnapshot>>();
+        let snapshots: Vec<_> = messages.get_cursor().read(messages).cloned().collect();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(
+            snapshots[0].weights,
+            HashMap::from([
+                ("happy".to_string(), 0.3),
+                (EYE_WIDE_EXPRESSION.to_string(), 0.15)
+            ])
+        );
+        assert_eq!(
+            snapshots[1].weights,
+            HashMap::from([(EYE_WIDE_EXPRESSION.to_string(), 0.15)])
+        );
+    }
+
+    /// Pipeline sink collecting written lines for inspection
+    struct CaptureSink(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl PipelineOutputSink for CaptureSink {
+        fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+            self.0.lock().unwrap().push(line.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_pipeline_output_writes_each_tracker_frame_once_except_while_scrubbing() {
+        let mut config = AppConfig::default();
+        config.expression_apply.master_expression_intensity = 0.5;
+        let lines = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_message::<ExpressionSnapshot>()
+            .insert_resource(Config { inner: config })
+            .insert_resource(CurrentExpressions {
+                expressions: vec![VrmExpression::new(VrmExpressionPreset::Happy, 0.5)],
+                custom: Vec::new(),
+                ts: 2.0,
+            })
+            .insert_resource(PipelineOutput {
+                sink: Some(Box::new(CaptureSink(lines.clone()))),
+            })
+            .init_resource::<AppliedExpressionWeights>()
+            .init_resource::<ExpressionEnableMask>()
+            .init_resource::<UnmappedExpressions>()
+            .init_resource::<CurrentShoulderPosition>()
+            .init_resource::<FrameHistory>()
+            .add_systems(Update, (apply_expressions, write_pipeline_output).chain());
+        app.update();
+        // Another app frame without a new tracker frame
+        app.update();
+        app.world_mut().resource_mut::<CurrentExpressions>().ts = 1.0;
+        app.world_mut().resource_mut::<FrameHistory>().scrub_index = Some(0);
+        app.update();
+
+        assert_eq!(
+            *lines.lock().unwrap(),
+            [r#"{"ts":2.0,"expressions":{"happy":0.25},"shoulder_midpoint":null}"#]
+        );
+    }
+
+    #[test]
+    fn test_inverted_expression_applies_without_tracked_expressions() {
+        let mut config = AppConfig::default();
+        config.expression_apply.overrides.insert(
+            "happy".to_string(),
+            config::ExpressionOverride { invert: true },
+        );
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_message::<ExpressionSnapshot>()
+            .insert_resource(Config { inner: config })
+            .init_resource::<CurrentExpressions>()
+            .init_resource::<AppliedExpressionWeights>()
+            .init_resource::<ExpressionEnableMask>()
+            .init_resource::<UnmappedExpressions>()
+            .add_systems(Update, apply_expressions);
+        let mesh = app
+            .world_mut()
+            .spawn((
+                VrmExpressionMap {
+                    expression_to_morphs: HashMap::from([("happy".to_string(), vec![(0, 1.0)])]),
+                },
+                MorphWeights::new(vec![0.0], None).unwrap(),
+            ))
+            .id();
+        app.update();
+
+        let morph_weights = app.world().get::<MorphWeights>(mesh).unwrap();
+        assert_eq!(morph_weights.weights(), &[1.0]);
+    }
+
+    #[test]
+    fn test_inverted_custom_expression_applies_once() {
+        let mut config = AppConfig::default();
+        config.expression_apply.overrides.insert(
+            EYE_WIDE_EXPRESSION.to_string(),
+            config::ExpressionOverride { invert: true },
+        );
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_message::<ExpressionSnapshot>()
+            .insert_resource(Config { inner: config })
+            .insert_resource(CurrentExpressions {
+                expressions: Vec::new(),
+                custom: vec![(EYE_WIDE_EXPRESSION, 0.25)],
+                ts: 0.0,
+            })
+            .init_resource::<AppliedExpressionWeights>()
+            .init_resource::<ExpressionEnableMask>()
+            .init_resource::<UnmappedExpressions>()
+            .add_systems(Update, apply_expressions);
+        let mesh = app
+            .world_mut()
+            .spawn((
+                VrmExpressionMap {
+                    expression_to_morphs: HashMap::from([(
+                        EYE_WIDE_EXPRESSION.to_string(),
+                        vec![(0, 1.0)],
+                    )]),
+                },
+                MorphWeights::new(vec![0.0], None).unwrap(),
+            ))
+            .id();
+        app.update();
+
+        let morph_weights = app.world().get::<MorphWeights>(mesh).unwrap();
+        assert_eq!(morph_weights.weights(), &[0.75]);
+
+        // Absent this frame, the inverted custom expression is fully on
+        app.world_mut()
+            .resource_mut::<CurrentExpressions>()
+            .custom
+            .clear();
+        app.update();
+        let morph_weights = app.world().get::<MorphWeights>(mesh).unwrap();
+        assert_eq!(morph_weights.weights(), &[1.0]);
+
+        let messages = app.world().resource::<Messages<ExpressionSnapshot>>();
+        let snapshots: Vec<_> = messages.get_cursor().read(messages).cloned().collect();
+        assert_eq!(
+            snapshots[0].weights,
+            HashMap::from([(EYE_WIDE_EXPRESSION.to_string(), 0.75)])
+        );
+        assert_eq!(
+            snapshots[1].weights,
+            HashMap::from([(EYE_WIDE_EXPRESSION.to_string(), 1.0)])
+        );
+    }
+
+    #[test]
+    fn test_disabled_expression_eases_to_zero() {
+        let mut mask = ExpressionEnableMask::default();
+        let expressions = vec![
+            VrmExpression::new(VrmExpressionPreset::LookLeft, 0.8),
+            VrmExpression::new(VrmExpressionPreset::Happy, 0.5),
+        ];
+        let weight = |applied: &[VrmExpression], preset| {
+            applied
+                .iter()
+                .find(|e| e.preset == preset)
+                .map_or(0.0, |e| e.weight)
+        };
+
+        assert!(!mask.toggle_group(ExpressionGroup::Gaze));
+        let dt = EXPRESSION_TOGGLE_FADE_SECS / 2.0;
+
+        // Halfway through the fade, gaze is eased rather than cut
+        let applied = mask.apply(&expressions, dt);
+        assert!((weight(&applied, VrmExpressionPreset::LookLeft) - 0.4).abs() < 1e-6);
+        assert_eq!(weight(&applied, VrmExpressionPreset::Happy), 0.5);
+
+        let applied = mask.apply(&expressions, dt);
+        assert_eq!(weight(&applied, VrmExpressionPreset::LookLeft), 0.0);
+        assert_eq!(weight(&applied, VrmExpressionPreset::Happy), 0.5);
+
+        // Re-enabling fades back in
+        assert!(mask.toggle_group(ExpressionGroup::Gaze));
+        let applied = mask.apply(&expressions, dt);
+        assert!((weight(&applied, VrmExpressionPreset::LookLeft) - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_disabled_preset_fades_within_enabled_group() {
+        let mut mask = ExpressionEnableMask::default();
+        let expressions = vec![
+            VrmExpression::new(VrmExpressionPreset::Happy, 0.5),
+            VrmExpression::new(VrmExpressionPreset::Sad, 0.5),
+        ];
+
+        assert!(!mask.toggle_preset(VrmExpressionPreset::Happy));
+        let applied = mask.apply(&expressions, EXPRESSION_TOGGLE_FADE_SECS);
+        assert_eq!(applied.len(), 1);
+        assert_eq!(applied[0].preset, VrmExpressionPreset::Sad);
+        assert_eq!(applied[0].weight, 0.5);
+
+        assert!(mask.toggle_preset(VrmExpressionPreset::Happy));
+        assert!(mask.is_enabled(VrmExpressionPreset::Happy));
+    }
+
+    #[test]
+    fn test_max_weight_rate_limits_jump() {
+        let mut applied = AppliedExpressionWeights::default();
+        let jump = vec![VrmExpression::new(VrmExpressionPreset::Happy, 1.0)];
+
+        // 4.0 per second over a 60 fps frame allows a step of 4/60
+        let dt = 1.0 / 60.0;
+        let limited = applied.limit(&jump, 4.0, dt);
+        assert_eq!(limited.len(), 1);
+        assert!((limited[0].weight - 4.0 * dt).abs() < 1e-6);
+
+        // A full second later the target is reached
+        let limited = applied.limit(&jump, 4.0, 1.0);
+        assert_eq!(limited[0].weight, 1.0);
+
+        // An expression that disappears fades out rather than dropping
+        let limited = applied.limit(&[], 4.0, 0.1);
+        assert!((limited[0].weight - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_face_tracking_app_plugin_registers_app() {
+        let mut app = App::new();
+        register_user_vrm_source(&mut app, PathBuf::new())
+            .add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .add_plugins(FaceTrackingAppPlugin::default());
+
+        assert!(app.is_plugin_added::<VrmLoaderPlugin>());
+        let world = app.world();
+        assert!(world.contains_resource::<Config>());
+        assert!(world.contains_resource::<ExpressionAdapter>());
+        assert!(world.contains_resource::<CurrentExpressions>());
+        assert!(world.contains_resource::<AppliedExpressionWeights>());
+
+        let system_ids = |app: &mut App, label: Interned<dyn ScheduleLabel>| {
+            app.world_mut().schedule_scope(label, |world, schedule| {
+                schedule.initialize(world).unwrap();
+                schedule
+                    .systems()
+                    .unwrap()
+                    .map(|(_, system)| system.type_id())
+                    .collect::<Vec<TypeId>>()
+            })
+        };
+        fn id_of<M>(system: impl IntoSystem<(), (), M>) -> TypeId {
+            IntoSystem::into_system(system).type_id()
+        }
+
+        let startup = system_ids(&mut app, Startup.intern());
+        assert!(startup.contains(&id_of(setup_tracker)));
+
+        let update = system_ids(&mut app, Update.intern());
+        assert!(update.contains(&id_of(receive_tracker_frames)));
+        assert!(update.contains(&id_of(dump_tracker_frames)));
+        assert!(update.contains(&id_of(build_expression_maps)));
+        assert!(update.contains(&id_of(apply_expressions)));
+        assert!(!update.contains(&id_of(setup_tracker)));
+    }
+
+    #[test]
+    #[should_panic(expected = "register_user_vrm_source")]
+    fn test_face_tracking_app_plugin_requires_user_vrm_source() {
+        App::new()
+            .add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .add_plugins(FaceTrackingAppPlugin::default());
+    }
+
+    fn face_frame(blendshapes: &[(&str, f32)]) -> TrackerFrame {
+        TrackerFrame {
+            ts: 0.0,
+            seq: None,
+            blendshapes: blendshapes
+                .iter()
+                .map(|&(name, value)| (name.to_string(), value))
+                .collect(),
+            pose_landmarks: Vec::new(),
+            pose_world_landmarks: Vec::new(),
+            camera: None,
+        }
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_download_destination_never_overwrites_other_models() {
+        let dir = std::env::temp_dir().join(format!(
+            "vrm1-face-tracking-download-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("avatar.vrm"), b"existing").unwrap();
+
+        // Same bytes reuse the saved file, different bytes get a new name
+        assert_eq!(
+            download_destination(&dir, "avatar.vrm", b"existing"),
+            dir.join("avatar.vrm")
+        );
+        assert_eq!(
+            download_destination(&dir, "avatar.vrm", b"other"),
+            dir.join("avatar-1.vrm")
+        );
+        assert_eq!(
+            download_destination(&dir, "new.vrm", b"other"),
+            dir.join("new.vrm")
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_camera_switch_waits_for_tracker_report() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_message::<TrackerFrameReceived>()
+            .insert_resource(TrackerControlChannel {
+                stdin: None,
+                camera_device_id: 0,
+            })
+            .add_systems(Update, observe_tracker_camera);
+
+        // Frames still reporting the old camera, e.g. after a failed switch
+        app.world_mut()
+            .write_message(TrackerFrameReceived(TrackerFrame {
+                camera: Some(CameraSelector::Index(0)),
+                ..face_frame(&[])
+            }));
+        app.update();
+        assert_eq!(
+            app.world()
+                .resource::<TrackerControlChannel>()
+                .camera_device_id,
+            0
+        );
+
+        app.world_mut()
+            .write_message(TrackerFrameReceived(TrackerFrame {
+                camera: Some(CameraSelector::Index(2)),
+                ..face_frame(&[])
+            }));
+        app.update();
+        assert_eq!(
+            app.world()
+                .resource::<TrackerControlChannel>()
+                .camera_device_id,
+            2
+        );
+    }
+
+    #[test]
+    fn test_calibration_waits_for_a_face_frame() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_message::<TrackerFrameReceived>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .insert_resource(ExpressionAdapter {
+                adapter: ArkitToVrmAdapter::new(),
+                calibrate_mouth_pending: false,
+                calibrate_neutral_pending: false,
+            })
+            .add_systems(Update, calibrate_expressions);
+
+        // `N` and `C` are pressed on a tick without tracker frames
+        let mut keyboard_input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keyboard_input.press(KeyCode::KeyN);
+        keyboard_input.press(KeyCode::KeyC);
+        app.update();
+        let mut keyboard_input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keyboard_input.release_all();
+        keyboard_input.clear();
+
+        // The next face frame still calibrates
+        app.world_mut()
+            .write_message(TrackerFrameReceived(face_frame(&[
+                ("jawOpen", 0.3),
+                ("browInnerUp", 0.2),
+            ])));
+        app.update();
+
+        let expression_adapter = app.world().resource::<ExpressionAdapter>();
+        assert!((expression_adapter.adapter.mouth_rest() - 0.3).abs() < 1e-6);
+        assert!(expression_adapter.adapter.neutral.is_calibrated());
+        assert!(!expression_adapter.calibrate_mouth_pending);
+        assert!(!expression_adapter.calibrate_neutral_pending);
+    }
+
+    #[test]
+    fn test_paused_frames_keep_tracking_quality_fresh() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_message::<TrackerFrameReceived>()
+            .insert_resource(TrackerReceiver { rx })
+            .insert_resource(TrackingPaused { paused: true })
+            .init_resource::<TrackerDiagnostics>()
+            .init_resource::<TrackingQuality>()
+            .add_systems(
+                Update,
+                (receive_tracker_frames, record_tracking_quality_inputs).chain(),
+            );
+
+        tx.send(face_frame(&[("jawOpen", 0.2)])).unwrap();
+        app.update();
+
+        let tracking_quality = app.world().resource::<TrackingQuality>();
+        assert!(tracking_quality.last_frame_secs.is_some());
+        assert!(tracking_quality.face_present);
+    }
+
+    #[test]
+    fn test_tracking_quality_bands() {
+        // Fresh frame with a confident pose and a face
+        assert!(compute_tracking_quality(0.9, true, 0.0) > 0.9);
+        // Fresh frame with a barely visible pose and no face
+        assert!(compute_tracking_quality(0.1, false, 0.0) < 0.1);
+        // Missing face alone halves the score
+        assert!((compute_tracking_quality(1.0, false, 0.1) - 0.5).abs() < 1e-6);
+
+        // Good inputs decay once the last frame gets old
+        let half_stale = (TRACKING_FRESH_SECS + TRACKING_STALE_SECS) * 0.5;
+        assert!((compute_tracking_quality(1.0, true, half_stale) - 0.5).abs() < 1e-6);
+        assert_eq!(compute_tracking_quality(1.0, true, 2.0), 0.0);
+    }
+}
@@ -0,0 +1,207 @@
+//! Streaming the applied tracking state to external tools as JSON lines.
+
+use crossbeam_channel::{Sender, TrySendError};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use expression_adapter::VrmExpressionPreset;
+
+/// How long to wait for the listening tool to accept the TCP connection
+const TCP_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Lines queued for the TCP writer thread before new frames are dropped
+const TCP_QUEUE_CAPACITY: usize = 64;
+
+/// Tracking state applied on one frame, as written to a [`PipelineOutputSink`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PipelineFrame {
+    /// Tracker timestamp of the frame (seconds since the Unix epoch)
+    pub ts: f64,
+    /// Applied preset and custom expression weights keyed by VRM expression name
+    pub expressions: BTreeMap<String, f32>,
+    /// Shoulder midpoint in MediaPipe world coordinates, if tracked
+    pub shoulder_midpoint: Option<[f32; 3]>,
+}
+
+impl PipelineFrame {
+    /// Frame from the applied weights keyed by expression name
+    pub fn new(
+        ts: f64,
+        weights: &HashMap<String, f32>,
+        shoulder_midpoint: Option<[f32; 3]>,
+    ) -> Self {
+        Self {
+            ts,
+            expressions: weights
+                .iter()
+                .map(|(name, weight)| (name.clone(), *weight))
+                .collect(),
+            shoulder_midpoint,
+        }
+    }
+
+    /// Encode the frame as one line of JSON, without the trailing newline
+    pub fn to_json_line(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Encode the expression weights as a VRM 1.0 weight object, e.g. `{"happy": 0.2}`
+    ///
+    /// Preset weights are keyed by their canonical VRM name. Other expressions
+    /// (e.g. `eyeWide`) are listed under `"custom"`, omitted when there are none.
+    pub fn to_vrm_weight_json(&self) -> serde_json::Value {
+        let mut presets = serde_json::Map::new();
+        let mut custom = serde_json::Map::new();
+        for (name, weight) in &self.expressions {
+            match VrmExpressionPreset::ALL
+                .iter()
+                .find(|preset| preset.as_str().eq_ignore_ascii_case(name))
+            {
+                Some(preset) => presets.insert(preset.as_str().to_string(), (*weight).into()),
+                None => custom.insert(name.clone(), (*weight).into()),
+            };
+        }
+        if !custom.is_empty() {
+            presets.insert("custom".to_string(), custom.into());
+        }
+        presets.into()
+    }
+}
+
+/// Destination for newline-delimited JSON pipeline frames
+pub trait PipelineOutputSink: Send + Sync {
+    /// Write one encoded frame followed by a newline
+    fn write_line(&mut self, line: &str) -> io::Result<()>;
+}
+
+/// Writes frames to a TCP connection opened at startup
+///
+/// Lines are written on a background thread, so a slow reader never stalls
+/// the app; frames are dropped while the queue is full.
+pub struct TcpSink {
+    tx: Sender<String>,
+}
+
+impl TcpSink {
+    /// Connect to a listening tool, e.g. `127.0.0.1:9000`
+    pub fn connect(address: &str) -> io::Result<Self> {
+        let stream = connect_with_timeout(address)?;
+        stream.set_nodelay(true)?;
+
+        let (tx, rx) = crossbeam_channel::bounded::<String>(TCP_QUEUE_CAPACITY);
+        std::thread::spawn(move || {
+            let mut stream = stream;
+            // Exiting drops the receiver, which write_line reports as an error
+            for line in rx {
+                if writeln!(stream, "{line}").is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self { tx })
+    }
+}
+
+/// Try each address `address` resolves to, waiting at most [`TCP_CONNECT_TIMEOUT`] on each
+fn connect_with_timeout(address: &str) -> io::Result<TcpStream> {
+    let mut last_error = None;
+    for socket_addr in address.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&socket_addr, TCP_CONNECT_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "address resolved to nothing")
+    }))
+}
+
+impl PipelineOutputSink for TcpSink {
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        match self.tx.try_send(line.to_string()) {
+            Ok(()) | Err(TrySendError::Full(_)) => Ok(()),
+            Err(TrySendError::Disconnected(_)) => Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "connection closed",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_pipeline_frame_json_line() {
+        let frame = PipelineFrame::new(
+            12.5,
+            &HashMap::from([
+                ("happy".to_string(), 0.5),
+                ("aa".to_string(), 0.25),
+                ("eyeWide".to_string(), 0.75),
+            ]),
+            Some([0.0, 0.5, -0.25]),
+        );
+        assert_eq!(
+            frame.to_json_line().unwrap(),
+            r#"{"ts":12.5,"expressions":{"aa":0.25,"eyeWide":0.75,"happy":0.5},"shoulder_midpoint":[0.0,0.5,-0.25]}"#
+        );
+
+        let untracked = PipelineFrame::new(1.0, &HashMap::new(), None);
+        assert_eq!(
+            untracked.to_json_line().unwrap(),
+            r#"{"ts":1.0,"expressions":{},"shoulder_midpoint":null}"#
+        );
+    }
+
+    #[test]
+    fn test_vrm_weight_json_uses_canonical_names() {
+        let frame = PipelineFrame::new(
+            0.0,
+            &HashMap::from([
+                ("blinkleft".to_string(), 0.5),
+                ("happy".to_string(), 0.25),
+                ("eyeWide".to_string(), 0.75),
+            ]),
+            None,
+        );
+        assert_eq!(
+            frame.to_vrm_weight_json(),
+            serde_json::json!({
+                "blinkLeft": 0.5,
+                "happy": 0.25,
+                "custom": {"eyeWide": 0.75}
+            })
+        );
+
+        let presets_only = PipelineFrame::new(0.0, &HashMap::from([("aa".to_string(), 1.0)]), None);
+        assert_eq!(
+            presets_only.to_vrm_weight_json(),
+            serde_json::json!({"aa": 1.0})
+        );
+    }
+
+    #[test]
+    fn test_tcp_sink_writes_lines_in_background() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let mut sink = TcpSink::connect(&address).unwrap();
+        sink.write_line("first").unwrap();
+        sink.write_line("second").unwrap();
+
+        let (stream, _) = listener.accept().unwrap();
+        let lines: Vec<String> = BufReader::new(stream)
+            .lines()
+            .take(2)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(lines, ["first", "second"]);
+    }
+}
@@ -3,6 +3,8 @@ import time
 import sys
 import os
 import argparse
+import queue
+import threading
 import cv2
 import mediapipe as mp
 from mediapipe.tasks import python
@@ -17,6 +19,34 @@ POSE_MODEL_PATH = "tools/pose_landmarker_full.task"
 # Maximum consecutive frame read failures before exiting
 MAX_FRAME_FAILURES = 30
 
+def read_commands(commands):
+    """Read JSON control commands from stdin and queue them for the capture loop."""
+    for line in sys.stdin:
+        line = line.strip()
+        if not line:
+            continue
+        try:
+            commands.put(json.loads(line))
+        except json.JSONDecodeError:
+            print(json.dumps({"error": f"Invalid command: {line}"}), file=sys.stderr, flush=True)
+
+
+def switch_camera(cap, camera):
+    """Open the requested camera, keeping the current one if it cannot be opened.
+
+    Returns the capture and whether the switch succeeded.
+    """
+    new_cap = cv2.VideoCapture(camera)
+    if not new_cap.isOpened():
+        print(json.dumps({
+            "error": f"Failed to open webcam ({camera}); keeping the current camera."
+        }), file=sys.stderr, flush=True)
+        new_cap.release()
+        return cap, False
+    cap.release()
+    return new_cap, True
+
+
 def main():
     parser = argparse.ArgumentParser(description="MediaPipe face and pose tracker")
     parser.add_argument(
@@ -25,8 +55,15 @@ def main():
         default=0,
         help="Video device index to use (default: 0)",
     )
+    parser.add_argument(
+        "--mode",
+        choices=["full", "face"],
+        default="full",
+        help="Track face and pose (full) or face blendshapes only (default: full)",
+    )
     args = parser.parse_args()
     camera_device_id = args.camera
+    track_pose = args.mode == "full"
 
     # Check if face model file exists
     if not os.path.exists(FACE_MODEL_PATH):
@@ -36,7 +73,7 @@ def main():
         sys.exit(1)
     
     # Check if pose model file exists
-    if not os.path.exists(POSE_MODEL_PATH):
+    if track_pose and not os.path.exists(POSE_MODEL_PATH):
         print(json.dumps({
             "error": f"Pose model file not found at {POSE_MODEL_PATH}. Please download it from: https://storage.googleapis.com/mediapipe-models/pose_landmarker/pose_landmarker_full/float16/1/pose_landmarker_full.task"
         }), file=sys.stderr, flush=True)
@@ -60,7 +97,7 @@ def main():
         running_mode=vision.RunningMode.VIDEO
     )
     
-    pose_landmarker = vision.PoseLandmarker.create_from_options(pose_options)
+    pose_landmarker = vision.PoseLandmarker.create_from_options(pose_options) if track_pose else None
     
     # Open webcam
     cap = cv2.VideoCapture(camera_device_id)
@@ -70,11 +107,23 @@ def main():
         }), file=sys.stderr, flush=True)
         sys.exit(1)
     
+    # Control commands from the Rust side (e.g. camera switches) arrive on stdin
+    commands = queue.Queue()
+    threading.Thread(target=read_commands, args=(commands,), daemon=True).start()
+
     frame_count = 0
     consecutive_failures = 0
     
     try:
         while True:
+            while not commands.empty():
+                command = commands.get()
+                if command.get("command") == "set_camera":
+                    cap, switched = switch_camera(cap, command["camera"])
+                    if switched:
+                        camera_device_id = command["camera"]
+                    consecutive_failures = 0
+
             success, frame = cap.read()
             if not success:
                 consecutive_failures += 1
@@ -107,12 +156,12 @@ def main():
                 for blendshape in face_result.face_blendshapes[0]:
                     blendshapes[blendshape.category_name] = blendshape.score
             
-            # Detect pose landmarks
-            pose_result = pose_landmarker.detect_for_video(mp_image, timestamp_ms)
+            # Detect pose landmarks (skipped in face mode)
+            pose_result = pose_landmarker.detect_for_video(mp_image, timestamp_ms) if pose_landmarker else None
             
             # Extract pose landmarks (33 3D landmarks in image coordinates)
             pose_landmarks = []
-            if pose_result.pose_landmarks and len(pose_result.pose_landmarks) > 0:
+            if pose_result and pose_result.pose_landmarks and len(pose_result.pose_landmarks) > 0:
                 for landmark in pose_result.pose_landmarks[0]:
                     pose_landmarks.append({
                         "x": landmark.x,
@@ -124,7 +173,7 @@ def main():
             
             # Extract pose world landmarks (33 3D landmarks in real-world coordinates)
             pose_world_landmarks = []
-            if pose_result.pose_world_landmarks and len(pose_result.pose_world_landmarks) > 0:
+            if pose_result and pose_result.pose_world_landmarks and len(pose_result.pose_world_landmarks) > 0:
                 for landmark in pose_result.pose_world_landmarks[0]:
                     pose_world_landmarks.append({
                         "x": landmark.x,
@@ -137,9 +186,12 @@ def main():
             # Output frame data in the expected format
             output = {
                 "ts": time.time(),
+                "seq": frame_count,
                 "blendshapes": blendshapes,
                 "pose_landmarks": pose_landmarks,
-                "pose_world_landmarks": pose_world_landmarks
+                "pose_world_landmarks": pose_world_landmarks,
+                # Reports which camera is active, so the app can tell a failed switch
+                "camera": camera_device_id
             }
             
             print(json.dumps(output), flush=True)
@@ -151,7 +203,8 @@ def main():
     finally:
         cap.release()
         face_landmarker.close()
-        pose_landmarker.close()
+        if pose_landmarker:
+            pose_landmarker.close()
 
 if __name__ == "__main__":
     main()